@@ -0,0 +1,126 @@
+//! @project       RoBustack-DL
+//! @organization  CodeBush Collective
+//! @license       GPL-3.0-only
+//! ---------------------------------------------------------------------------
+//! AI PROVENANCE & HUMAN-IN-THE-LOOP (HITL) METADATA:
+//! - Prompt Engineering: Gemini 3 Flash (Strategy, Scoping & Context Tuning)
+//! - Code Generation:   Gemini 3 Pro (Core Systems Engineering & Async Logic)
+//! - Technical Review:  Claude 4.6 Opus (Security Audit & Idiomatic Refinement)
+//! - HITL Verification: Collisio-Adolebitque - AA0614550BDC21F1 (Manual Audit & Final Validation)
+//! ---------------------------------------------------------------------------
+//! Verified Date: 2026-02-15
+//! Integrity: GPG-Signed | HITL-Certified
+//!
+//! Local download cache — content-addressed by source URL.
+//!
+//! # Design
+//! Independent of the idempotency manifest (`integrity::Manifest`, which is
+//! keyed by *content* hash): this cache is keyed by the SHA-256 of the
+//! *source URL*, so a re-run can skip the network entirely for an asset it
+//! has already fetched, even before the new content's hash is known. Each
+//! cache entry is a flat file named after its URL hash, holding the exact
+//! response bytes.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+/// Compute the cache file path for a given source URL within `cache_dir`.
+#[must_use]
+pub fn entry_path(cache_dir: &Path, url: &str) -> PathBuf {
+    cache_dir.join(crate::integrity::sha256_hex(url.as_bytes()))
+}
+
+/// Look up a cached copy of `url`, returning its path if present on disk.
+#[must_use]
+pub fn lookup(cache_dir: &Path, url: &str) -> Option<PathBuf> {
+    let path = entry_path(cache_dir, url);
+    path.is_file().then_some(path)
+}
+
+/// Store `bytes` in the cache under `url`'s hash, creating `cache_dir` if
+/// needed. Returns the path the entry was written to.
+///
+/// # Errors
+/// Returns `anyhow::Error` if `cache_dir` cannot be created or the entry
+/// cannot be written.
+pub fn store(cache_dir: &Path, url: &str, bytes: &[u8]) -> anyhow::Result<PathBuf> {
+    std::fs::create_dir_all(cache_dir).context("Failed to create cache directory")?;
+    let path = entry_path(cache_dir, url);
+    std::fs::write(&path, bytes)
+        .with_context(|| format!("Failed to write cache entry {}", path.display()))?;
+    Ok(path)
+}
+
+/// Materialize a cached entry at `dest`, hardlinking where possible and
+/// falling back to a copy across filesystem boundaries. Returns the size
+/// of the materialized file in bytes.
+///
+/// # Errors
+/// Returns `anyhow::Error` if `dest`'s parent cannot be created or the
+/// entry cannot be linked/copied.
+pub fn materialize(cached: &Path, dest: &Path) -> anyhow::Result<u64> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if std::fs::hard_link(cached, dest).is_err() {
+        std::fs::copy(cached, dest)
+            .with_context(|| format!("Failed to copy cache entry to {}", dest.display()))?;
+    }
+    Ok(std::fs::metadata(dest)?.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_path_is_deterministic() {
+        let dir = Path::new("cache");
+        assert_eq!(
+            entry_path(dir, "https://example.com/a.png"),
+            entry_path(dir, "https://example.com/a.png")
+        );
+    }
+
+    #[test]
+    fn entry_path_differs_per_url() {
+        let dir = Path::new("cache");
+        assert_ne!(
+            entry_path(dir, "https://example.com/a.png"),
+            entry_path(dir, "https://example.com/b.png")
+        );
+    }
+
+    #[test]
+    fn lookup_misses_when_absent() {
+        let tmp = std::env::temp_dir().join("robustack-dl-cache-test-miss");
+        let _ = std::fs::remove_dir_all(&tmp);
+        assert!(lookup(&tmp, "https://example.com/missing.png").is_none());
+    }
+
+    #[test]
+    fn store_then_lookup_hits() {
+        let tmp = std::env::temp_dir().join("robustack-dl-cache-test-hit");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let url = "https://example.com/hit.png";
+        store(&tmp, url, b"hello").unwrap();
+        let hit = lookup(&tmp, url);
+        assert!(hit.is_some());
+        assert_eq!(std::fs::read(hit.unwrap()).unwrap(), b"hello");
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn materialize_copies_bytes_to_dest() {
+        let tmp = std::env::temp_dir().join("robustack-dl-cache-test-materialize");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let url = "https://example.com/asset.bin";
+        let cached = store(&tmp, url, b"payload").unwrap();
+        let dest = tmp.join("out").join("asset.bin");
+        let size = materialize(&cached, &dest).unwrap();
+        assert_eq!(size, 7);
+        assert_eq!(std::fs::read(&dest).unwrap(), b"payload");
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
@@ -23,6 +23,7 @@
 use std::path::PathBuf;
 
 use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
 
 /// Multi-line version banner emitted by `robustack-dl -V`.
 const VERSION_BANNER: &str = concat!(
@@ -44,6 +45,15 @@ pub enum OutputFormat {
     Md,
     /// Plain text with headings preserved.
     Txt,
+    /// Single EPUB 3 ebook bundling every post as a spine chapter.
+    Epub,
+    /// Self-contained single-file HTML: every `<img>`, `<link
+    /// rel="stylesheet">`, `<script>`, and absolute CSS `url(...)` reference
+    /// inside a fetched stylesheet is fetched and rewritten to an embedded
+    /// `data:` URI (relative CSS `url(...)` references are left as-is), so
+    /// `--download-images`/`--download-files` are implied and the
+    /// images/files directories are never created.
+    Single,
 }
 
 impl std::fmt::Display for OutputFormat {
@@ -52,6 +62,8 @@ impl std::fmt::Display for OutputFormat {
             Self::Html => write!(f, "html"),
             Self::Md => write!(f, "md"),
             Self::Txt => write!(f, "txt"),
+            Self::Epub => write!(f, "epub"),
+            Self::Single => write!(f, "single"),
         }
     }
 }
@@ -77,6 +89,83 @@ impl std::fmt::Display for ImageQuality {
     }
 }
 
+/// Packaging format for the generated archive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ArchiveFormat {
+    /// Leave the output directory as a plain directory tree (default).
+    Dir,
+    /// Package the output directory into a single `.zip` file.
+    Zip,
+    /// Package the output directory into a single gzip-compressed tarball.
+    TarGz,
+}
+
+impl std::fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dir => write!(f, "dir"),
+            Self::Zip => write!(f, "zip"),
+            Self::TarGz => write!(f, "tar-gz"),
+        }
+    }
+}
+
+/// Digest algorithm used for content-addressed storage.
+///
+/// Recorded alongside every [`crate::integrity::ManifestEntry`] so a
+/// manifest produced with one algorithm can coexist with entries produced
+/// by another (e.g. after switching the default) and still verify.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgo {
+    /// SHA-256 (the original default; matches every pre-existing manifest).
+    Sha256,
+    /// BLAKE3, hashed with rayon-backed tree hashing over a memory-mapped
+    /// file so large attachments (podcasts, video) hash across cores.
+    Blake3,
+}
+
+impl Default for HashAlgo {
+    fn default() -> Self {
+        Self::Sha256
+    }
+}
+
+impl std::fmt::Display for HashAlgo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sha256 => write!(f, "sha256"),
+            Self::Blake3 => write!(f, "blake3"),
+        }
+    }
+}
+
+/// Compression applied to written output files and downloaded assets.
+///
+/// Opt-in: the idempotency `sha256`/digest in [`crate::integrity::ManifestEntry`]
+/// is always computed over the *uncompressed* content, so switching this on
+/// or off between runs doesn't invalidate the manifest — only the bytes on
+/// disk and their filename suffix change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompressionMode {
+    /// Gzip (`.gz` suffix).
+    Gzip,
+    /// Brotli (`.br` suffix).
+    Br,
+    /// Zstandard (`.zst` suffix).
+    Zstd,
+}
+
+impl std::fmt::Display for CompressionMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gzip => write!(f, "gzip"),
+            Self::Br => write!(f, "br"),
+            Self::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Top-level CLI
 // ---------------------------------------------------------------------------
@@ -113,17 +202,52 @@ pub struct Cli {
     #[arg(short = 'r', long, global = true, default_value_t = 2)]
     pub rate: u32,
 
+    /// Maximum retry attempts for retryable HTTP failures (default: 3).
+    #[arg(long, global = true, default_value_t = 3)]
+    pub max_retries: u32,
+
+    /// Base delay in milliseconds for exponential backoff between retries.
+    #[arg(long, global = true, default_value_t = 500)]
+    pub base_backoff_ms: u64,
+
+    /// Maximum number of in-flight requests at once (default: 4).
+    #[arg(long, global = true, default_value_t = 4)]
+    pub max_concurrent: u32,
+
+    /// Directory for the HTTP conditional-request cache (ETag/Last-Modified
+    /// revalidation). Unset lets `download` pick its own default (a
+    /// `.robustack-cache` directory under `--output`, unless `--no-cache`
+    /// is set); other subcommands leave HTTP caching disabled. Re-running
+    /// against the same directory lets unchanged pages be skipped or
+    /// revalidated with a `304` instead of re-downloaded in full.
+    #[arg(long, global = true)]
+    pub http_cache_dir: Option<PathBuf>,
+
+    /// Disable transparent gzip/brotli/deflate decompression (store-raw
+    /// mode: responses are written byte-exact as they arrive on the wire).
+    #[arg(long, global = true)]
+    pub no_decompress: bool,
+
     /// Enable verbose output (sets RUST_LOG=debug).
     #[arg(short = 'v', long, global = true)]
     pub verbose: bool,
 
-    /// Only process posts published on or after this date (YYYY-MM-DD).
+    /// Only process posts published on or after this date. Accepts
+    /// `YYYY-MM-DD` (start of day UTC) or a full RFC 3339 timestamp.
     #[arg(long, global = true)]
     pub after: Option<String>,
 
-    /// Only process posts published on or before this date (YYYY-MM-DD).
+    /// Only process posts published on or before this date. Accepts
+    /// `YYYY-MM-DD` (end of day UTC) or a full RFC 3339 timestamp.
     #[arg(long, global = true)]
     pub before: Option<String>,
+
+    /// If the Substack JSON API errors or returns an unparseable body
+    /// (paywalled, rate-limited, or a non-Substack mirror), fall back to
+    /// parsing `{url}/feed` as RSS/Atom instead of failing outright. Feed
+    /// metadata is lower-fidelity than the API (e.g. no stable post `id`).
+    #[arg(long, global = true)]
+    pub allow_rss_fallback: bool,
 }
 
 // ---------------------------------------------------------------------------
@@ -139,6 +263,8 @@ pub enum Commands {
     List(ListArgs),
     /// Verify archive integrity against a manifest.
     Audit(AuditArgs),
+    /// Serve a finished archive over local, read-only HTTP.
+    Serve(ServeArgs),
     /// Generate shell completions to stdout.
     Completions(CompletionsArgs),
     /// Display the current version of the app.
@@ -161,7 +287,9 @@ pub struct DownloadArgs {
     #[arg(short, long, default_value = ".")]
     pub output: PathBuf,
 
-    /// Output format: "html", "md", "txt".
+    /// Output format: "html", "md", "txt", "epub" (one ebook bundling every
+    /// post), "single" (self-contained HTML with every resource inlined as a
+    /// `data:` URI).
     #[arg(short, long, value_enum, default_value_t = OutputFormat::Html)]
     pub format: OutputFormat,
 
@@ -201,6 +329,75 @@ pub struct DownloadArgs {
     /// Create an archive index page linking all downloaded posts.
     #[arg(long)]
     pub create_archive: bool,
+
+    /// Package the output directory as a single file: "dir" (no packaging,
+    /// default), "zip", or "tar-gz".
+    #[arg(long, value_enum, default_value_t = ArchiveFormat::Dir)]
+    pub archive_format: ArchiveFormat,
+
+    /// Directory name for the content-addressed download cache (relative
+    /// to output). Reused across runs to skip re-fetching unchanged assets.
+    #[arg(long, default_value = "cache")]
+    pub cache_dir: String,
+
+    /// Disable the local download cache — always re-fetch from the
+    /// network. Also disables `download`'s default HTTP conditional-request
+    /// cache (ETag/Last-Modified), so every post and listing page is
+    /// fetched fresh even when `--http-cache-dir` isn't set.
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Digest algorithm for content-addressed storage: "sha256" (default)
+    /// or "blake3".
+    #[arg(long, value_enum, default_value_t = HashAlgo::Sha256)]
+    pub hash_algo: HashAlgo,
+
+    /// Compress written post bodies and downloaded assets: "gzip", "br",
+    /// or "zstd". Unset (the default) writes raw, uncompressed files.
+    /// Filenames gain the matching suffix (e.g. `.html.gz`, `.bin.br`,
+    /// `.bin.zst`).
+    #[arg(long, value_enum)]
+    pub compress: Option<CompressionMode>,
+
+    /// Extract the main article body before converting to "md"/"txt",
+    /// dropping navigation, footers, share/subscribe widgets, and comment
+    /// scaffolding. Has no effect on "html" output, which always keeps the
+    /// raw document.
+    #[arg(long)]
+    pub readability: bool,
+
+    /// Comma-separated list of host suffixes allowed for embedded resource
+    /// fetches (images, attachments, stylesheets, scripts), e.g.
+    /// "substackcdn.com,substack.com". A suffix matches any host ending
+    /// with it, so "substackcdn.com" also matches "foo.substackcdn.com".
+    /// If empty (the default), every host is allowed unless excluded by
+    /// `--domain-deny`.
+    #[arg(long, default_value = "")]
+    pub domain_allow: String,
+
+    /// Comma-separated list of host suffixes denied for embedded resource
+    /// fetches, using the same suffix matching as `--domain-allow`. Takes
+    /// precedence over `--domain-allow` — a host matching both is denied.
+    #[arg(long, default_value = "")]
+    pub domain_deny: String,
+
+    /// Resume partial downloads across process restarts. A `.part` scratch
+    /// file left behind by a previous run is validated against a persisted
+    /// `ETag`/`Last-Modified` sidecar via a conditional `If-Range` request
+    /// before it's trusted; if the remote file has changed, the download
+    /// restarts from scratch. Without this flag, any leftover `.part` file
+    /// is discarded up front and every download starts clean.
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Path to an ed25519 secret key. When set, the manifest is signed
+    /// after it's written and the detached signature is saved as
+    /// `manifest.json.sig`, so a third party with the matching public key
+    /// can later confirm the manifest itself wasn't tampered with (see
+    /// `audit --verify-key`). Accepts either 32 raw key bytes or the same
+    /// bytes as 64 hex characters.
+    #[arg(long)]
+    pub sign_key: Option<PathBuf>,
 }
 
 // ---------------------------------------------------------------------------
@@ -213,6 +410,21 @@ pub struct AuditArgs {
     /// Path to the manifest.json to verify against.
     #[arg(short, long, default_value = "manifest.json")]
     pub manifest: PathBuf,
+
+    /// Additionally verify each entry's recorded SRI record (`sha256` +
+    /// `sha384`) when present, not just the primary digest. Reports a
+    /// tampered file even if it happens to collide on the primary
+    /// algorithm alone.
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Path to an ed25519 public key. When set, `manifest.json.sig` is
+    /// required and checked against this key before any hashes are
+    /// trusted — an unsigned manifest, a missing signature, or one that
+    /// doesn't verify is a hard failure. Accepts either 32 raw key bytes
+    /// or the same bytes as 64 hex characters.
+    #[arg(long)]
+    pub verify_key: Option<PathBuf>,
 }
 
 // ---------------------------------------------------------------------------
@@ -227,6 +439,28 @@ pub struct ListArgs {
     pub url: String,
 }
 
+// ---------------------------------------------------------------------------
+// Serve arguments
+// ---------------------------------------------------------------------------
+
+/// Arguments for the `serve` subcommand.
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Archive directory to serve (the `--output` directory from a prior
+    /// `download` run).
+    #[arg(short, long, default_value = ".")]
+    pub dir: PathBuf,
+
+    /// Address:port to bind the HTTP server to.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub bind: String,
+
+    /// HTTP Basic auth credentials as "user:pass". Unset serves the
+    /// archive without authentication.
+    #[arg(long)]
+    pub auth: Option<secrecy::SecretString>,
+}
+
 // ---------------------------------------------------------------------------
 // Completions arguments
 // ---------------------------------------------------------------------------
@@ -318,6 +552,10 @@ mod tests {
                 assert_eq!(args.image_quality, ImageQuality::High);
                 assert_eq!(args.images_dir, "images");
                 assert_eq!(args.files_dir, "files");
+                assert_eq!(args.cache_dir, "cache");
+                assert!(!args.no_cache);
+                assert_eq!(args.archive_format, ArchiveFormat::Dir);
+                assert_eq!(args.hash_algo, HashAlgo::Sha256);
                 assert!(!args.download_images);
                 assert!(!args.download_files);
                 assert!(!args.dry_run);
@@ -328,6 +566,69 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_download_archive_format() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+            "--create-archive",
+            "--archive-format",
+            "tar-gz",
+        ])
+        .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => {
+                assert_eq!(args.archive_format, ArchiveFormat::TarGz);
+            }
+            _ => panic!("expected Download command"),
+        }
+    }
+
+    #[test]
+    fn parse_download_cache_flags() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+            "--cache-dir",
+            "my-cache",
+            "--no-cache",
+        ])
+        .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => {
+                assert_eq!(args.cache_dir, "my-cache");
+                assert!(args.no_cache);
+            }
+            _ => panic!("expected Download command"),
+        }
+    }
+
+    #[test]
+    fn parse_download_hash_algo() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+            "--hash-algo",
+            "blake3",
+        ])
+        .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => {
+                assert_eq!(args.hash_algo, HashAlgo::Blake3);
+            }
+            _ => panic!("expected Download command"),
+        }
+    }
+
     #[test]
     fn parse_download_format_txt() {
         let cli = Cli::try_parse_from([
@@ -364,6 +665,123 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_download_domain_allow_and_deny() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://x.com",
+            "--domain-allow",
+            "substackcdn.com,substack.com",
+            "--domain-deny",
+            "evil.substackcdn.com",
+        ])
+        .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => {
+                assert_eq!(args.domain_allow, "substackcdn.com,substack.com");
+                assert_eq!(args.domain_deny, "evil.substackcdn.com");
+            }
+            _ => panic!("expected Download command"),
+        }
+    }
+
+    #[test]
+    fn parse_download_resume_flag() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://x.com",
+            "--resume",
+        ])
+        .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => assert!(args.resume),
+            _ => panic!("expected Download command"),
+        }
+    }
+
+    #[test]
+    fn parse_download_sign_key() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://x.com",
+            "--sign-key",
+            "/keys/signer.key",
+        ])
+        .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => {
+                assert_eq!(args.sign_key, Some(PathBuf::from("/keys/signer.key")));
+            }
+            _ => panic!("expected Download command"),
+        }
+    }
+
+    #[test]
+    fn parse_download_defaults_sign_key_to_none() {
+        let cli = Cli::try_parse_from(["robustack-dl", "download", "--url", "https://x.com"])
+            .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => assert!(args.sign_key.is_none()),
+            _ => panic!("expected Download command"),
+        }
+    }
+
+    #[test]
+    fn parse_download_readability_flag() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://x.com",
+            "--readability",
+        ])
+        .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => assert!(args.readability),
+            _ => panic!("expected Download command"),
+        }
+    }
+
+    #[test]
+    fn parse_download_format_single() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://x.com",
+            "--format",
+            "single",
+        ])
+        .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => assert_eq!(args.format, OutputFormat::Single),
+            _ => panic!("expected Download command"),
+        }
+    }
+
+    #[test]
+    fn parse_download_readability_defaults_to_false() {
+        let cli = Cli::try_parse_from(["robustack-dl", "download", "--url", "https://x.com"])
+            .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Download(args) => assert!(!args.readability),
+            _ => panic!("expected Download command"),
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Unit: Global flags
     // -----------------------------------------------------------------------
@@ -406,6 +824,126 @@ mod tests {
         assert_eq!(cli.rate, 2);
     }
 
+    #[test]
+    fn parse_retry_flags_defaults() {
+        let cli = Cli::try_parse_from(["robustack-dl", "download", "--url", "https://x.com"])
+            .expect("valid args should parse");
+
+        assert_eq!(cli.max_retries, 3);
+        assert_eq!(cli.base_backoff_ms, 500);
+    }
+
+    #[test]
+    fn parse_retry_flags_custom() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "--max-retries",
+            "5",
+            "--base-backoff-ms",
+            "1000",
+            "download",
+            "--url",
+            "https://x.com",
+        ])
+        .expect("valid args should parse");
+
+        assert_eq!(cli.max_retries, 5);
+        assert_eq!(cli.base_backoff_ms, 1000);
+    }
+
+    #[test]
+    fn parse_max_concurrent_defaults_to_four() {
+        let cli = Cli::try_parse_from(["robustack-dl", "download", "--url", "https://x.com"])
+            .expect("valid args should parse");
+
+        assert_eq!(cli.max_concurrent, 4);
+    }
+
+    #[test]
+    fn parse_max_concurrent_custom() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "--max-concurrent",
+            "8",
+            "download",
+            "--url",
+            "https://x.com",
+        ])
+        .expect("valid args should parse");
+
+        assert_eq!(cli.max_concurrent, 8);
+    }
+
+    #[test]
+    fn parse_http_cache_dir_defaults_to_none() {
+        let cli = Cli::try_parse_from(["robustack-dl", "download", "--url", "https://x.com"])
+            .expect("valid args should parse");
+
+        assert_eq!(cli.http_cache_dir, None);
+    }
+
+    #[test]
+    fn parse_http_cache_dir_custom() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "--http-cache-dir",
+            "/tmp/robustack-http-cache",
+            "download",
+            "--url",
+            "https://x.com",
+        ])
+        .expect("valid args should parse");
+
+        assert_eq!(
+            cli.http_cache_dir,
+            Some(PathBuf::from("/tmp/robustack-http-cache"))
+        );
+    }
+
+    #[test]
+    fn parse_no_decompress_defaults_to_false() {
+        let cli = Cli::try_parse_from(["robustack-dl", "download", "--url", "https://x.com"])
+            .expect("valid args should parse");
+
+        assert!(!cli.no_decompress);
+    }
+
+    #[test]
+    fn parse_no_decompress_flag_sets_true() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "--no-decompress",
+            "download",
+            "--url",
+            "https://x.com",
+        ])
+        .expect("valid args should parse");
+
+        assert!(cli.no_decompress);
+    }
+
+    #[test]
+    fn parse_allow_rss_fallback_defaults_to_false() {
+        let cli = Cli::try_parse_from(["robustack-dl", "download", "--url", "https://x.com"])
+            .expect("valid args should parse");
+
+        assert!(!cli.allow_rss_fallback);
+    }
+
+    #[test]
+    fn parse_allow_rss_fallback_flag_sets_true() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "--allow-rss-fallback",
+            "download",
+            "--url",
+            "https://x.com",
+        ])
+        .expect("valid args should parse");
+
+        assert!(cli.allow_rss_fallback);
+    }
+
     #[test]
     fn parse_global_verbose_flag() {
         let cli = Cli::try_parse_from([
@@ -500,6 +1038,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_audit_defaults_verify_to_false() {
+        let cli = Cli::try_parse_from(["robustack-dl", "audit"]).expect("valid args should parse");
+
+        match cli.command {
+            Commands::Audit(args) => assert!(!args.verify),
+            _ => panic!("expected Audit command"),
+        }
+    }
+
+    #[test]
+    fn parse_audit_verify_flag_is_captured() {
+        let cli = Cli::try_parse_from(["robustack-dl", "audit", "--verify"])
+            .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Audit(args) => assert!(args.verify),
+            _ => panic!("expected Audit command"),
+        }
+    }
+
+    #[test]
+    fn parse_audit_verify_key() {
+        let cli = Cli::try_parse_from(["robustack-dl", "audit", "--verify-key", "/keys/signer.pub"])
+            .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Audit(args) => {
+                assert_eq!(args.verify_key, Some(PathBuf::from("/keys/signer.pub")));
+            }
+            _ => panic!("expected Audit command"),
+        }
+    }
+
+    #[test]
+    fn parse_audit_defaults_verify_key_to_none() {
+        let cli = Cli::try_parse_from(["robustack-dl", "audit"]).expect("valid args should parse");
+
+        match cli.command {
+            Commands::Audit(args) => assert!(args.verify_key.is_none()),
+            _ => panic!("expected Audit command"),
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Unit: List
     // -----------------------------------------------------------------------
@@ -528,6 +1110,50 @@ mod tests {
         assert!(result.is_err(), "list without --url should fail");
     }
 
+    // -----------------------------------------------------------------------
+    // Unit: Serve
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn parse_serve_defaults() {
+        let cli = Cli::try_parse_from(["robustack-dl", "serve"]).expect("valid args should parse");
+
+        match cli.command {
+            Commands::Serve(args) => {
+                assert_eq!(args.dir, PathBuf::from("."));
+                assert_eq!(args.bind, "127.0.0.1:8080");
+                assert!(args.auth.is_none());
+            }
+            _ => panic!("expected Serve command"),
+        }
+    }
+
+    #[test]
+    fn parse_serve_with_dir_bind_and_auth() {
+        use secrecy::ExposeSecret;
+
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "serve",
+            "--dir",
+            "./my-archive",
+            "--bind",
+            "0.0.0.0:9090",
+            "--auth",
+            "reader:hunter2",
+        ])
+        .expect("valid args should parse");
+
+        match cli.command {
+            Commands::Serve(args) => {
+                assert_eq!(args.dir, PathBuf::from("./my-archive"));
+                assert_eq!(args.bind, "0.0.0.0:9090");
+                assert_eq!(args.auth.unwrap().expose_secret(), "reader:hunter2");
+            }
+            _ => panic!("expected Serve command"),
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Unit: Version
     // -----------------------------------------------------------------------
@@ -663,6 +1289,8 @@ mod tests {
         assert_eq!(OutputFormat::Html.to_string(), "html");
         assert_eq!(OutputFormat::Md.to_string(), "md");
         assert_eq!(OutputFormat::Txt.to_string(), "txt");
+        assert_eq!(OutputFormat::Epub.to_string(), "epub");
+        assert_eq!(OutputFormat::Single.to_string(), "single");
     }
 
     #[test]
@@ -672,6 +1300,73 @@ mod tests {
         assert_eq!(ImageQuality::Low.to_string(), "low");
     }
 
+    #[test]
+    fn hash_algo_display() {
+        assert_eq!(HashAlgo::Sha256.to_string(), "sha256");
+        assert_eq!(HashAlgo::Blake3.to_string(), "blake3");
+    }
+
+    #[test]
+    fn hash_algo_default_is_sha256() {
+        assert_eq!(HashAlgo::default(), HashAlgo::Sha256);
+    }
+
+    #[test]
+    fn compression_mode_display() {
+        assert_eq!(CompressionMode::Gzip.to_string(), "gzip");
+        assert_eq!(CompressionMode::Br.to_string(), "br");
+        assert_eq!(CompressionMode::Zstd.to_string(), "zstd");
+    }
+
+    #[test]
+    fn parse_compress_defaults_to_none() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+        ])
+        .expect("valid args");
+        let Commands::Download(args) = cli.command else {
+            panic!("expected Download")
+        };
+        assert!(args.compress.is_none());
+    }
+
+    #[test]
+    fn parse_compress_flag_sets_mode() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+            "--compress",
+            "gzip",
+        ])
+        .expect("valid args");
+        let Commands::Download(args) = cli.command else {
+            panic!("expected Download")
+        };
+        assert_eq!(args.compress, Some(CompressionMode::Gzip));
+    }
+
+    #[test]
+    fn parse_compress_flag_accepts_zstd() {
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+            "--compress",
+            "zstd",
+        ])
+        .expect("valid args");
+        let Commands::Download(args) = cli.command else {
+            panic!("expected Download")
+        };
+        assert_eq!(args.compress, Some(CompressionMode::Zstd));
+    }
+
     // -----------------------------------------------------------------------
     // Unit: Clap internal consistency
     // -----------------------------------------------------------------------
@@ -19,10 +19,97 @@
 //! Handlers depend on `&dyn HttpClient`, enabling:
 //! - Unit testing with mock clients (no network I/O).
 //! - Future swap to alternative HTTP backends without changing handlers.
+//!
+//! # Retries
+//! `ReqwestClient` retries idempotent GETs on connection errors, timeouts,
+//! and HTTP 408/429/500/502/503/504, up to `max_retries` attempts. A
+//! server-supplied `Retry-After` header (seconds or HTTP-date form) always
+//! wins; otherwise the delay is exponential backoff with full jitter,
+//! capped at [`BACKOFF_CAP_MS`].
+//!
+//! [`HttpClient::get_to_file`] additionally retries on a declared-length
+//! mismatch, and does so by resuming rather than restarting: a `.part`
+//! file left behind by a failed attempt is continued with a `Range`
+//! request on the next attempt instead of being re-downloaded whole.
+//!
+//! # Resuming across process restarts
+//! The in-call retry above survives a flaky connection, but a `.part` file
+//! left behind by a killed or crashed process is only trusted across a
+//! fresh `get_to_file` call when `resume` is enabled. In that mode, a
+//! sidecar `<dest>.part.meta.json` persists the `ETag`/`Last-Modified`
+//! validator and declared total byte count alongside the partial body, and
+//! the next attempt's `Range` request carries an `If-Range` header built
+//! from that validator: the server answers `206 Partial Content` (append)
+//! only if the remote file hasn't changed, falling back to a full `200 OK`
+//! — which truncates and restarts the part file from zero — otherwise.
+//! With `resume` disabled (the default), any leftover `.part` file and
+//! sidecar are discarded at the start of every `get_to_file` call instead.
+//!
+//! # Throttling, concurrency, and cancellation
+//! Every request first passes through a [`TokenBucket`] that enforces
+//! `rate_limit` requests/second and a `Semaphore` that caps in-flight
+//! requests at `max_concurrent`. Both waits, and every in-flight send or
+//! retry sleep, race against an internal `CancellationToken` so a caller
+//! can abort all pending fetches via [`ReqwestClient::cancel`] — used to
+//! make Ctrl-C and fatal errors stop outstanding requests promptly rather
+//! than letting them run to completion.
+//!
+//! # HTTP cache
+//! When `http_cache_dir` is set, `get_bytes`/`get_text` consult
+//! [`crate::http_cache`] before hitting the network: a fresh entry (under
+//! its `Cache-Control: max-age`) is returned without a request at all, and
+//! a stale-but-validated entry is revalidated with `If-None-Match`/
+//! `If-Modified-Since`, reusing the cached body on a `304`. This is
+//! separate from [`crate::cache`], which caches downloaded binary assets
+//! by source URL rather than HTTP response semantics.
+//!
+//! # Transparent decompression
+//! Every request sends `Accept-Encoding: br, gzip, deflate` unless
+//! `decompress` is disabled (store-raw mode, for byte-exact mirrors of the
+//! wire response). The response body is streamed through the matching
+//! `async-compression` decoder named by `Content-Encoding` before it is
+//! buffered, hashed, or written to disk, so every `HttpClient` method —
+//! including [`HttpClient::get_to_file`]'s incremental hash — always sees
+//! decoded bytes.
+//!
+//! # Redirects
+//! The underlying `reqwest::Client` is built with
+//! `redirect::Policy::none()` — redirects are followed manually by
+//! [`ReqwestClient::request_with_retry`] so a custom-domain publication
+//! that 301/302s to `*.substack.com` keeps working. Each `Location` is
+//! resolved against the URL that produced it (so relative redirects work),
+//! capped at [`MAX_REDIRECT_HOPS`] hops, and rejected outright on a loop.
+//!
+//! A `--cookie-val` session cookie is never handed to `reqwest` as a
+//! standing `cookie_provider` — that would let its cookie-jar middleware
+//! re-attach it to any request whose host the jar matches, including a
+//! redirect target, regardless of what headers were stripped beforehand.
+//! Instead [`ReqwestClient::request_with_retry`] adds the `Cookie` header
+//! itself, per attempt, only while the URL being requested is still
+//! same-origin (scheme, host, and port) with the URL the caller originally
+//! asked for; once a hop crosses origins the header is simply never added
+//! again for the rest of the chain. `Authorization` is stripped the same
+//! way, should a future caller ever set one.
 
-use std::time::Duration;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
 
+use anyhow::Context;
 use async_trait::async_trait;
+use futures_util::StreamExt;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncRead;
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
+use tokio_util::sync::CancellationToken;
+
+/// Upper bound on any single computed backoff delay.
+const BACKOFF_CAP_MS: u64 = 30_000;
+
+/// Maximum number of redirect hops [`ReqwestClient::request_with_retry`]
+/// follows before giving up — matches curl's/most browsers' default.
+const MAX_REDIRECT_HOPS: u32 = 10;
 
 // ---------------------------------------------------------------------------
 // HttpClient trait — dependency inversion boundary
@@ -37,21 +124,155 @@ use async_trait::async_trait;
 pub trait HttpClient: Send + Sync + std::fmt::Debug {
     /// Perform an HTTP GET and return the response body as bytes.
     ///
+    /// When an HTTP cache is configured, a fresh cached copy short-circuits
+    /// the network entirely and a stale one is revalidated conditionally.
+    ///
     /// # Errors
     /// Returns `anyhow::Error` on network failure, timeout, or non-2xx status.
     async fn get_bytes(&self, url: &str) -> anyhow::Result<Vec<u8>>;
 
+    /// Perform an HTTP GET and return the body alongside the server's
+    /// `Content-Type` header (if present), for callers that need to
+    /// combine it with content sniffing ([`crate::sniff`]) to pick a file
+    /// extension for an otherwise-ambiguous asset.
+    ///
+    /// The default implementation delegates to [`HttpClient::get_bytes`]
+    /// and reports no content type; [`ReqwestClient`] overrides this to
+    /// surface the actual response header.
+    ///
+    /// # Errors
+    /// Returns `anyhow::Error` on network failure, timeout, or non-2xx status.
+    async fn get_bytes_with_type(&self, url: &str) -> anyhow::Result<(Vec<u8>, Option<String>)> {
+        Ok((self.get_bytes(url).await?, None))
+    }
+
     /// Perform an HTTP GET and return the response body as a UTF-8 string.
     ///
+    /// Subject to the same HTTP caching as [`HttpClient::get_bytes`].
+    ///
     /// # Errors
     /// Returns `anyhow::Error` on network failure, timeout, non-2xx status,
     /// or invalid UTF-8 in the response body.
     async fn get_text(&self, url: &str) -> anyhow::Result<String>;
 
+    /// Perform an HTTP GET and stream the response body directly to `dest`
+    /// without buffering it in memory, returning the total bytes written.
+    ///
+    /// Prefer this over [`HttpClient::get_bytes`] for large assets (image
+    /// originals, PDF/EPUB attachments) where materializing the whole body
+    /// as a `Vec<u8>` is wasteful.
+    ///
+    /// # Errors
+    /// Returns `anyhow::Error` on network failure, timeout, non-2xx status,
+    /// or if `dest` cannot be created/written.
+    async fn download_to(&self, url: &str, dest: &std::path::Path) -> anyhow::Result<u64>;
+
+    /// Perform an HTTP GET, streaming the response to `dest` chunk-by-chunk
+    /// while feeding each chunk into a running SHA-256 hash, and return
+    /// `(bytes_written, sha256_hex, content_type)` so callers can record a
+    /// manifest entry and sniff a file extension without a second read of
+    /// the file.
+    ///
+    /// The body is written to a sibling `.part` file (`dest.with_extension
+    /// ("part")`) and atomically renamed into place only once the transfer
+    /// completes. A failed attempt leaves that `.part` file in place rather
+    /// than deleting it, and is retried up to `max_retries` times: if it's
+    /// still there (non-empty) on the next attempt, the request resends as
+    /// `Range: bytes=<n>-` and appends to it instead of starting over,
+    /// falling back to a full re-download if the server answers with `200`
+    /// instead of `206 Partial Content`. A declared `Content-Length`/
+    /// `Content-Range` total that doesn't match what was actually written
+    /// also triggers a retry; a `Content-Length` on a body that is being
+    /// decoded counts encoded bytes, so it isn't checked.
+    ///
+    /// Resuming a `.part` file left behind by a *previous, separate* call
+    /// (e.g. the process was killed) additionally requires `resume` to be
+    /// enabled, and validates the remote file hasn't changed via a
+    /// persisted `ETag`/`Last-Modified` sidecar before trusting it — see
+    /// the module-level "Resuming across process restarts" section.
+    ///
+    /// # Errors
+    /// Returns `anyhow::Error` on network failure, timeout, non-2xx status,
+    /// a length mismatch, or if `dest`'s `.part` file cannot be
+    /// created/written/renamed, after all retries are exhausted.
+    async fn get_to_file(
+        &self,
+        url: &str,
+        dest: &std::path::Path,
+    ) -> anyhow::Result<(u64, String, Option<String>)>;
+
     /// Returns the configured rate limit (requests per second).
     fn rate_limit(&self) -> u32;
 }
 
+// ---------------------------------------------------------------------------
+// TokenBucket — requests-per-second throttling
+// ---------------------------------------------------------------------------
+
+/// Token bucket used to throttle outgoing requests to a configured
+/// requests-per-second rate.
+///
+/// Tokens refill continuously (not in discrete ticks): each [`acquire`]
+/// call tops up the fractional counter based on elapsed time since the
+/// last refill, then either consumes a token immediately or sleeps until
+/// one becomes available.
+///
+/// [`acquire`]: TokenBucket::acquire
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    rate: f64,
+    state: Mutex<TokenBucketState>,
+}
+
+#[derive(Debug)]
+struct TokenBucketState {
+    available: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Create a bucket with `capacity = rate` tokens, refilled at `rate`
+    /// tokens/second. Starts full so the first `rate` requests are not
+    /// artificially delayed.
+    fn new(rate: u32) -> Self {
+        let rate = f64::from(rate.max(1));
+        Self {
+            capacity: rate,
+            rate,
+            state: Mutex::new(TokenBucketState {
+                available: rate,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a single token is available, then consume it.
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                let elapsed = state.last_refill.elapsed().as_secs_f64();
+                state.available = (state.available + elapsed * self.rate).min(self.capacity);
+                state.last_refill = Instant::now();
+
+                if state.available >= 1.0 {
+                    state.available -= 1.0;
+                    None
+                } else {
+                    let deficit = 1.0 - state.available;
+                    Some(Duration::from_secs_f64(deficit / self.rate))
+                }
+            };
+
+            match wait {
+                None => return,
+                Some(delay) => tokio::time::sleep(delay).await,
+            }
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // ReqwestClient — production implementation
 // ---------------------------------------------------------------------------
@@ -60,12 +281,21 @@ pub trait HttpClient: Send + Sync + std::fmt::Debug {
 #[derive(Debug)]
 pub struct ReqwestClient {
     inner: reqwest::Client,
+    cookie: Option<(String, String)>,
     rate_limit: u32,
+    max_retries: u32,
+    base_backoff_ms: u64,
+    token_bucket: TokenBucket,
+    concurrency: Semaphore,
+    cancel_token: CancellationToken,
+    http_cache_dir: Option<std::path::PathBuf>,
+    decompress: bool,
+    resume: bool,
 }
 
 impl Default for ReqwestClient {
     fn default() -> Self {
-        Self::new(None, None, 2)
+        Self::new(None, None, 2, 3, 500, 4, None, true, false)
     }
 }
 
@@ -74,12 +304,39 @@ impl ReqwestClient {
     ///
     /// # Arguments
     /// - `proxy` — Optional HTTP/SOCKS5 proxy URL.
-    /// - `cookie` — Optional `(name, value)` pair for Substack session auth.
+    /// - `cookie` — Optional `(name, value)` pair for Substack session auth,
+    ///   sent as an explicit `Cookie` header by
+    ///   [`ReqwestClient::request_with_retry`] — never installed as a
+    ///   standing `cookie_provider`, so it can't be re-attached to a
+    ///   redirect target on another origin.
     /// - `rate_limit` — Maximum requests per second.
+    /// - `max_retries` — Maximum retry attempts for retryable failures.
+    /// - `base_backoff_ms` — Base delay (in ms) for exponential backoff.
+    /// - `max_concurrent` — Maximum number of in-flight requests at once.
+    /// - `http_cache_dir` — Directory for the HTTP conditional-request
+    ///   cache; `None` disables HTTP caching.
+    /// - `decompress` — Negotiate and transparently decode
+    ///   `Content-Encoding: br`/`gzip`/`deflate` responses. Disable for
+    ///   store-raw mode (byte-exact mirrors of the wire response).
+    /// - `resume` — Trust a `.part` file left behind by a previous,
+    ///   separate [`HttpClient::get_to_file`] call (validated against a
+    ///   persisted `ETag`/`Last-Modified` sidecar) instead of discarding it
+    ///   and starting over.
     #[must_use]
-    pub fn new(proxy: Option<&str>, cookie: Option<(&str, &str)>, rate_limit: u32) -> Self {
+    pub fn new(
+        proxy: Option<&str>,
+        cookie: Option<(&str, &str)>,
+        rate_limit: u32,
+        max_retries: u32,
+        base_backoff_ms: u64,
+        max_concurrent: u32,
+        http_cache_dir: Option<std::path::PathBuf>,
+        decompress: bool,
+        resume: bool,
+    ) -> Self {
         let mut builder = reqwest::Client::builder()
             .timeout(Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::none())
             .user_agent(concat!(
                 "RoBustack-DL/",
                 env!("CARGO_PKG_VERSION"),
@@ -90,19 +347,31 @@ impl ReqwestClient {
             builder = builder.proxy(p);
         }
 
-        if let Some((name, value)) = cookie {
-            let jar = reqwest::cookie::Jar::default();
-            let cookie_str = format!("{name}={value}");
-            jar.add_cookie_str(&cookie_str, &"https://substack.com".parse().unwrap());
-            builder = builder.cookie_provider(std::sync::Arc::new(jar));
-        }
-
         Self {
             inner: builder.build().unwrap_or_default(),
+            cookie: cookie.map(|(name, value)| (name.to_owned(), value.to_owned())),
             rate_limit,
+            max_retries,
+            base_backoff_ms,
+            token_bucket: TokenBucket::new(rate_limit),
+            concurrency: Semaphore::new(max_concurrent.max(1) as usize),
+            cancel_token: CancellationToken::new(),
+            http_cache_dir,
+            decompress,
+            resume,
         }
     }
 
+    /// Abort all pending and future requests made through this client.
+    ///
+    /// Any in-flight `acquire`/send/retry-sleep wakes up and returns an
+    /// `anyhow::Error` instead of completing. Intended for Ctrl-C handling
+    /// and for stopping outstanding fetches once a fatal error elsewhere
+    /// has made the rest of the run pointless.
+    pub fn cancel(&self) {
+        self.cancel_token.cancel();
+    }
+
     /// Build a `ReqwestClient` from an `AppConfig`.
     #[must_use]
     pub fn from_config(config: &crate::config::AppConfig) -> Self {
@@ -113,20 +382,700 @@ impl ReqwestClient {
             }
             _ => None,
         };
-        Self::new(config.proxy.as_deref(), cookie, config.rate_limit)
+        Self::new(
+            config.proxy.as_deref(),
+            cookie,
+            config.rate_limit,
+            config.max_retries,
+            config.base_backoff_ms,
+            config.max_concurrent,
+            config.http_cache_dir.clone(),
+            config.decompress,
+            config.resume,
+        )
+    }
+
+    /// Build a GET request for `url`, adding a negotiated `Accept-Encoding`
+    /// header when [`ReqwestClient::decompress`] is enabled so the server
+    /// is offered compression that [`decode_response`] then undoes.
+    fn base_request(&self, url: &str) -> reqwest::RequestBuilder {
+        let builder = self.inner.get(url);
+        if self.decompress {
+            builder.header(reqwest::header::ACCEPT_ENCODING, "br, gzip, deflate")
+        } else {
+            builder
+        }
+    }
+
+    /// Wait for both a concurrency permit and a rate-limit token, racing
+    /// both waits against cancellation so a caller isn't stuck queued up
+    /// behind a full bucket/semaphore after [`ReqwestClient::cancel`].
+    async fn throttle(&self) -> anyhow::Result<SemaphorePermit<'_>> {
+        let permit = tokio::select! {
+            biased;
+            () = self.cancel_token.cancelled() => anyhow::bail!("Request cancelled"),
+            permit = self.concurrency.acquire() => permit.expect("concurrency semaphore is never closed"),
+        };
+        tokio::select! {
+            biased;
+            () = self.cancel_token.cancelled() => anyhow::bail!("Request cancelled"),
+            () = self.token_bucket.acquire() => {}
+        }
+        Ok(permit)
+    }
+
+    /// Perform a GET built fresh on each attempt by `build`, manually
+    /// following redirects and retrying retryable failures until
+    /// `max_retries` is exhausted, then return the (possibly
+    /// still-failing) response.
+    ///
+    /// `build` is called once per attempt with the URL currently being
+    /// requested (rather than the request being built once up front) so
+    /// conditional-request headers set by the HTTP cache are carried
+    /// through retries, redirects are rebuilt against their new target,
+    /// and a `reqwest::Request` (which isn't `Clone`) never needs to be
+    /// reused across attempts.
+    ///
+    /// A `3xx` other than `304 Not Modified` (which callers need to see
+    /// themselves, for cache revalidation) is followed by resolving its
+    /// `Location` against the URL that produced it — so both absolute and
+    /// relative redirect targets work — up to [`MAX_REDIRECT_HOPS`] hops,
+    /// erroring on a loop or on exceeding that cap. The configured
+    /// `--cookie-val` session `Cookie` is added to each attempt only while
+    /// `current_url` is still same-origin with `url` (the caller's original
+    /// target) — never installed as a standing `cookie_provider`, so once a
+    /// hop crosses origins it is simply never added again for the rest of
+    /// the chain, and a foreign redirect target never sees it. Any
+    /// `Authorization` header is stripped the same way, should a future
+    /// caller ever set one.
+    async fn request_with_retry(
+        &self,
+        url: &str,
+        build: impl Fn(&str) -> reqwest::RequestBuilder,
+    ) -> anyhow::Result<reqwest::Response> {
+        let mut attempt = 0u32;
+        let mut current_url = url.to_string();
+        let mut hops = 0u32;
+        let mut visited = std::collections::HashSet::new();
+        visited.insert(current_url.clone());
+
+        loop {
+            let mut builder = build(&current_url);
+            if same_origin(url, &current_url) {
+                if let Some((name, value)) = &self.cookie {
+                    builder = builder.header(reqwest::header::COOKIE, format!("{name}={value}"));
+                }
+            }
+            let mut request = builder.build()?;
+            if !same_origin(url, &current_url) {
+                request.headers_mut().remove(reqwest::header::AUTHORIZATION);
+                request.headers_mut().remove(reqwest::header::COOKIE);
+            }
+
+            let sent = tokio::select! {
+                biased;
+                () = self.cancel_token.cancelled() => anyhow::bail!("Request cancelled"),
+                result = self.inner.execute(request) => result,
+            };
+            match sent {
+                Ok(resp)
+                    if resp.status().is_redirection()
+                        && resp.status() != reqwest::StatusCode::NOT_MODIFIED =>
+                {
+                    hops += 1;
+                    if hops > MAX_REDIRECT_HOPS {
+                        anyhow::bail!(
+                            "Exceeded {MAX_REDIRECT_HOPS} redirect hops starting from {url}"
+                        );
+                    }
+                    let location = resp
+                        .headers()
+                        .get(reqwest::header::LOCATION)
+                        .and_then(|v| v.to_str().ok())
+                        .ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "{} redirect from {current_url} has no Location header",
+                                resp.status()
+                            )
+                        })?
+                        .to_owned();
+                    let next = resolve_redirect(&current_url, &location)?;
+                    if !visited.insert(next.clone()) {
+                        anyhow::bail!("Redirect loop detected following {url} (revisited {next})");
+                    }
+                    tracing::debug!(
+                        from = %current_url,
+                        to = %next,
+                        status = %resp.status(),
+                        "Following redirect"
+                    );
+                    current_url = next;
+                }
+                Ok(resp) if resp.status().is_success() => return Ok(resp),
+                Ok(resp) if is_retryable_status(resp.status()) && attempt < self.max_retries => {
+                    let delay = retry_after_delay(resp.headers())
+                        .unwrap_or_else(|| backoff_with_jitter(self.base_backoff_ms, attempt));
+                    tracing::warn!(
+                        attempt = attempt + 1,
+                        status = %resp.status(),
+                        delay_ms = delay.as_millis() as u64,
+                        url = %current_url,
+                        "Retrying after throttled/failed response"
+                    );
+                    tokio::select! {
+                        biased;
+                        () = self.cancel_token.cancelled() => anyhow::bail!("Request cancelled"),
+                        () = tokio::time::sleep(delay) => {}
+                    }
+                    attempt += 1;
+                }
+                Ok(resp) => return Ok(resp.error_for_status()?),
+                Err(e) if (e.is_timeout() || e.is_connect()) && attempt < self.max_retries => {
+                    let delay = backoff_with_jitter(self.base_backoff_ms, attempt);
+                    tracing::warn!(
+                        attempt = attempt + 1,
+                        error = %e,
+                        delay_ms = delay.as_millis() as u64,
+                        url = %current_url,
+                        "Retrying after network error"
+                    );
+                    tokio::select! {
+                        biased;
+                        () = self.cancel_token.cancelled() => anyhow::bail!("Request cancelled"),
+                        () = tokio::time::sleep(delay) => {}
+                    }
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Fetch `url`'s body as bytes, consulting the HTTP cache (when
+    /// configured) before and around the network request.
+    ///
+    /// - A fresh cached entry (per `Cache-Control: max-age`) is returned
+    ///   without any request at all.
+    /// - A stale cached entry is revalidated with `If-None-Match`/
+    ///   `If-Modified-Since`; a `304` response reuses the cached body and
+    ///   refreshes its freshness window, while a `200` overwrites the
+    ///   cache entry with the new body and validators.
+    /// - With no cache configured, this is equivalent to a plain
+    ///   `request_with_retry` + buffer.
+    async fn fetch_cacheable(&self, url: &str) -> anyhow::Result<Vec<u8>> {
+        let Some(cache_dir) = self.http_cache_dir.as_deref() else {
+            let _permit = self.throttle().await?;
+            let resp = self.request_with_retry(url, |u| self.base_request(u)).await?;
+            return self.decode_body(url, resp).await;
+        };
+
+        let cached = crate::http_cache::load(cache_dir, url);
+
+        if let Some(entry) = &cached {
+            if crate::http_cache::is_fresh(&entry.meta) {
+                tracing::debug!(url = %url, "HTTP cache hit (fresh)");
+                return Ok(entry.body.clone());
+            }
+        }
+
+        let _permit = self.throttle().await?;
+        let cached_for_headers = cached.clone();
+        let resp = self
+            .request_with_retry(url, |u| {
+                let mut builder = self.base_request(u);
+                if let Some(entry) = &cached_for_headers {
+                    if let Some(etag) = &entry.meta.etag {
+                        builder = builder.header(reqwest::header::IF_NONE_MATCH, etag);
+                    }
+                    if let Some(last_modified) = &entry.meta.last_modified {
+                        builder = builder.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+                    }
+                }
+                builder
+            })
+            .await?;
+
+        if resp.status() == reqwest::StatusCode::NOT_MODIFIED {
+            if let Some(entry) = cached {
+                tracing::debug!(url = %url, "HTTP cache hit (revalidated via 304)");
+                let mut meta = entry.meta;
+                meta.fetched_at = chrono::Utc::now().to_rfc3339();
+                crate::http_cache::store(cache_dir, url, &entry.body, &meta)?;
+                return Ok(entry.body);
+            }
+            // A 304 with nothing cached (e.g. the entry was evicted out of
+            // band) can't be served — fall through to a fresh request.
+            let _permit = self.throttle().await?;
+            let resp = self.request_with_retry(url, |u| self.base_request(u)).await?;
+            return self.cache_response(cache_dir, url, resp).await;
+        }
+
+        tracing::debug!(url = %url, status = %resp.status(), "HTTP cache miss");
+        self.cache_response(cache_dir, url, resp).await
+    }
+
+    /// Read `resp`'s body through [`decode_response`], logging which codec
+    /// (if any) was applied.
+    async fn decode_body(&self, url: &str, resp: reqwest::Response) -> anyhow::Result<Vec<u8>> {
+        use tokio::io::AsyncReadExt;
+
+        let (encoding, mut reader) = decode_response(resp, self.decompress);
+        tracing::debug!(url = %url, codec = ?encoding, decompress = self.decompress, "Decoding response body");
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body).await?;
+        Ok(body)
+    }
+
+    /// Buffer `resp`'s body, store it (with its validators) in the HTTP
+    /// cache, and return the body.
+    async fn cache_response(
+        &self,
+        cache_dir: &std::path::Path,
+        url: &str,
+        resp: reqwest::Response,
+    ) -> anyhow::Result<Vec<u8>> {
+        let (no_store, max_age_secs) = resp
+            .headers()
+            .get(reqwest::header::CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok())
+            .map(crate::http_cache::parse_cache_control)
+            .unwrap_or((false, None));
+        let etag = resp
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let last_modified = resp
+            .headers()
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+
+        let body = self.decode_body(url, resp).await?;
+
+        if no_store || (etag.is_none() && last_modified.is_none() && max_age_secs.is_none()) {
+            crate::http_cache::evict(cache_dir, url);
+            return Ok(body);
+        }
+
+        let meta = crate::http_cache::CacheMeta {
+            etag,
+            last_modified,
+            max_age_secs,
+            fetched_at: chrono::Utc::now().to_rfc3339(),
+        };
+        crate::http_cache::store(cache_dir, url, &body, &meta)?;
+        Ok(body)
+    }
+
+    /// Run a single [`HttpClient::get_to_file`] attempt: resume from
+    /// `part_path` with `Range: bytes=<n>-` if it's already non-empty,
+    /// otherwise start fresh. Leaves `part_path` on disk on failure so the
+    /// caller's retry loop can resume from it instead of starting over.
+    ///
+    /// When `self.resume` is set and a persisted [`PartMeta`] sidecar
+    /// exists for `part_path`, the `Range` request also carries an
+    /// `If-Range` header built from its validator, so a remote file that
+    /// changed since the partial download started comes back as a full
+    /// `200 OK` (handled identically to a server that ignores `Range`
+    /// outright) instead of silently appended-to.
+    async fn get_to_file_once(
+        &self,
+        url: &str,
+        dest: &std::path::Path,
+        part_path: &std::path::Path,
+    ) -> anyhow::Result<(u64, String, Option<String>)> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let resume_from = tokio::fs::metadata(part_path).await.map(|m| m.len()).unwrap_or(0);
+        let part_meta = if self.resume && resume_from > 0 {
+            load_part_meta(part_path)
+        } else {
+            None
+        };
+        let if_range = part_meta
+            .as_ref()
+            .and_then(|m| m.etag.clone().or_else(|| m.last_modified.clone()));
+
+        let _permit = self.throttle().await?;
+        let resp = if resume_from > 0 {
+            self.request_with_retry(url, |u| {
+                let mut req = self
+                    .inner
+                    .get(u)
+                    .header(reqwest::header::RANGE, format!("bytes={resume_from}-"));
+                if let Some(validator) = &if_range {
+                    req = req.header(reqwest::header::IF_RANGE, validator.as_str());
+                }
+                req
+            })
+            .await?
+        } else {
+            self.request_with_retry(url, |u| self.base_request(u)).await?
+        };
+
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let etag = resp
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let last_modified = resp
+            .headers()
+            .get(reqwest::header::LAST_MODIFIED)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+
+        // A Range request the server didn't honor — or rejected via
+        // `If-Range` because the remote file changed — comes back as a
+        // plain `200` with the *full* body from the start. Either way we
+        // can't append, so fall back to overwriting `part_path` from
+        // scratch rather than corrupting it.
+        let resumed = resume_from > 0 && resp.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+        let start_offset = if resumed { resume_from } else { 0 };
+
+        let declared_total = if resumed {
+            resp.headers()
+                .get(reqwest::header::CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.rsplit('/').next())
+                .and_then(|total| total.parse::<u64>().ok())
+        } else if self.decompress && resp.headers().contains_key(reqwest::header::CONTENT_ENCODING) {
+            // `Content-Length` counts the encoded bytes, not the decoded
+            // ones written below, so there's no total to check them against.
+            None
+        } else {
+            resp.headers()
+                .get(reqwest::header::CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+        };
+
+        if self.resume {
+            let meta = PartMeta { etag, last_modified, expected_total: declared_total };
+            let _ = store_part_meta(part_path, &meta);
+        }
+
+        // A resumed body can't be decoded correctly from a byte range
+        // alone, and binary assets are rarely served compressed in the
+        // first place, so treat it as raw bytes rather than running it
+        // through `decode_response`.
+        let mut reader: Pin<Box<dyn AsyncRead + Send>> = if resumed {
+            let byte_stream = resp
+                .bytes_stream()
+                .map(|chunk| chunk.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e)));
+            Box::pin(tokio_util::io::StreamReader::new(byte_stream))
+        } else {
+            let (encoding, reader) = decode_response(resp, self.decompress);
+            tracing::debug!(url = %url, codec = ?encoding, decompress = self.decompress, "Decoding response body");
+            reader
+        };
+
+        let mut hasher = Sha256::new();
+        let mut file = if resumed {
+            let mut existing = tokio::fs::File::open(part_path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Cannot reopen {} to resume: {e}", part_path.display()))?;
+            let mut buf = vec![0u8; 64 * 1024];
+            loop {
+                let read = existing.read(&mut buf).await?;
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buf[..read]);
+            }
+            tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(part_path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Cannot append to {}: {e}", part_path.display()))?
+        } else {
+            tokio::fs::File::create(part_path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Cannot create {}: {e}", part_path.display()))?
+        };
+
+        let mut written = start_offset;
+        let mut buf = vec![0u8; 64 * 1024];
+
+        let transfer: anyhow::Result<()> = async {
+            loop {
+                let read = tokio::select! {
+                    biased;
+                    () = self.cancel_token.cancelled() => anyhow::bail!("Request cancelled"),
+                    read = reader.read(&mut buf) => read?,
+                };
+                if read == 0 {
+                    break;
+                }
+                hasher.update(&buf[..read]);
+                file.write_all(&buf[..read]).await?;
+                written += read as u64;
+            }
+            file.flush().await?;
+            Ok(())
+        }
+        .await;
+
+        transfer?;
+        drop(file);
+
+        if let Some(total) = declared_total {
+            if written != total {
+                anyhow::bail!(
+                    "Downloaded {written} of {total} declared bytes for {url} — will retry"
+                );
+            }
+        }
+
+        tokio::fs::rename(part_path, dest).await.map_err(|e| {
+            anyhow::anyhow!(
+                "Cannot rename {} to {}: {e}",
+                part_path.display(),
+                dest.display()
+            )
+        })?;
+        remove_part_meta(part_path);
+
+        Ok((written, hex::encode(hasher.finalize()), content_type))
+    }
+}
+
+/// Validators persisted alongside a `.part` file (as `<part_path>.meta.json`)
+/// so [`ReqwestClient::get_to_file`] can tell, on a later call, whether a
+/// leftover partial download still matches the remote file before trusting
+/// it — see the module-level "Resuming across process restarts" section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PartMeta {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    expected_total: Option<u64>,
+}
+
+/// Path of the `PartMeta` sidecar for a given `.part` file.
+fn part_meta_path(part_path: &std::path::Path) -> std::path::PathBuf {
+    let mut name = part_path.as_os_str().to_owned();
+    name.push(".meta.json");
+    std::path::PathBuf::from(name)
+}
+
+/// Load the persisted `PartMeta` sidecar for `part_path`, if present and
+/// parseable.
+fn load_part_meta(part_path: &std::path::Path) -> Option<PartMeta> {
+    let raw = std::fs::read(part_meta_path(part_path)).ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+/// Persist `meta` as the sidecar for `part_path`.
+fn store_part_meta(part_path: &std::path::Path, meta: &PartMeta) -> anyhow::Result<()> {
+    let json = serde_json::to_vec_pretty(meta).context("Failed to serialize part meta")?;
+    std::fs::write(part_meta_path(part_path), json).context("Failed to write part meta sidecar")?;
+    Ok(())
+}
+
+/// Remove the `PartMeta` sidecar for `part_path`, ignoring errors (e.g.
+/// already absent).
+fn remove_part_meta(part_path: &std::path::Path) {
+    let _ = std::fs::remove_file(part_meta_path(part_path));
+}
+
+/// Returns `true` for HTTP statuses worth retrying (server overload or
+/// transient gateway failures).
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+/// Resolve a redirect `Location` header against the URL that produced it,
+/// handling both absolute (`https://other.example/x`) and relative
+/// (`/x`, `x`) forms.
+fn resolve_redirect(current_url: &str, location: &str) -> anyhow::Result<String> {
+    let base = reqwest::Url::parse(current_url)
+        .map_err(|e| anyhow::anyhow!("Invalid URL {current_url}: {e}"))?;
+    let next = base
+        .join(location)
+        .map_err(|e| anyhow::anyhow!("Invalid Location header {location:?}: {e}"))?;
+    Ok(next.to_string())
+}
+
+/// Returns `true` if `a` and `b` share a scheme, host, and port — used to
+/// decide whether a redirect hop stays within the original origin (safe to
+/// carry `Authorization`/`Cookie` forward) or crosses to another one (must
+/// drop them). Unparseable URLs are never considered same-origin.
+fn same_origin(a: &str, b: &str) -> bool {
+    let origin = |u: &str| {
+        reqwest::Url::parse(u).ok().map(|parsed| {
+            (
+                parsed.scheme().to_owned(),
+                parsed.host_str().map(str::to_owned),
+                parsed.port_or_known_default(),
+            )
+        })
+    };
+    match (origin(a), origin(b)) {
+        (Some(oa), Some(ob)) => oa == ob,
+        _ => false,
+    }
+}
+
+/// Parse a `Retry-After` header, honoring both the delta-seconds and
+/// HTTP-date forms. Returns `None` if the header is absent or unparsable.
+fn retry_after_delay(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    let trimmed = value.trim();
+
+    if let Ok(secs) = trimmed.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let target = chrono::DateTime::parse_from_rfc2822(trimmed).ok()?;
+    let delta = target.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    delta.to_std().ok()
+}
+
+/// Exponential backoff with full jitter: a random duration in
+/// `[0, min(cap, base * 2^attempt)]`.
+fn backoff_with_jitter(base_ms: u64, attempt: u32) -> Duration {
+    let max_ms = base_ms
+        .saturating_mul(1u64 << attempt.min(20))
+        .min(BACKOFF_CAP_MS);
+    let jittered = rand::thread_rng().gen_range(0..=max_ms);
+    Duration::from_millis(jittered)
+}
+
+/// Split `resp` into its `Content-Encoding` (if any) and an `AsyncRead`
+/// over its body, decoding through the matching `async-compression` codec
+/// when `decompress` is `true`. With `decompress` disabled, or an
+/// unrecognized/absent `Content-Encoding`, the body passes through
+/// unmodified — giving store-raw mode byte-exact wire responses.
+fn decode_response(
+    resp: reqwest::Response,
+    decompress: bool,
+) -> (Option<String>, Pin<Box<dyn AsyncRead + Send>>) {
+    let encoding = resp
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_ascii_lowercase);
+
+    let byte_stream = resp
+        .bytes_stream()
+        .map(|chunk| chunk.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e)));
+    let reader = tokio::io::BufReader::new(tokio_util::io::StreamReader::new(byte_stream));
+
+    if !decompress {
+        return (encoding, Box::pin(reader));
+    }
+
+    match encoding.as_deref() {
+        Some("gzip" | "x-gzip") => (
+            encoding,
+            Box::pin(async_compression::tokio::bufread::GzipDecoder::new(reader)),
+        ),
+        Some("br") => (
+            encoding,
+            Box::pin(async_compression::tokio::bufread::BrotliDecoder::new(reader)),
+        ),
+        Some("deflate") => (
+            encoding,
+            Box::pin(async_compression::tokio::bufread::DeflateDecoder::new(reader)),
+        ),
+        _ => (encoding, Box::pin(reader)),
     }
 }
 
 #[async_trait]
 impl HttpClient for ReqwestClient {
     async fn get_bytes(&self, url: &str) -> anyhow::Result<Vec<u8>> {
-        let resp = self.inner.get(url).send().await?.error_for_status()?;
-        Ok(resp.bytes().await?.to_vec())
+        self.fetch_cacheable(url).await
+    }
+
+    async fn get_bytes_with_type(&self, url: &str) -> anyhow::Result<(Vec<u8>, Option<String>)> {
+        let _permit = self.throttle().await?;
+        let resp = self.request_with_retry(url, |u| self.base_request(u)).await?;
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned);
+        let body = self.decode_body(url, resp).await?;
+        Ok((body, content_type))
     }
 
     async fn get_text(&self, url: &str) -> anyhow::Result<String> {
-        let resp = self.inner.get(url).send().await?.error_for_status()?;
-        Ok(resp.text().await?)
+        let bytes = self.fetch_cacheable(url).await?;
+        Ok(String::from_utf8(bytes)?)
+    }
+
+    async fn download_to(&self, url: &str, dest: &std::path::Path) -> anyhow::Result<u64> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let _permit = self.throttle().await?;
+        let resp = self.request_with_retry(url, |u| self.base_request(u)).await?;
+        let (encoding, mut reader) = decode_response(resp, self.decompress);
+        tracing::debug!(url = %url, codec = ?encoding, decompress = self.decompress, "Decoding response body");
+
+        let mut file = tokio::fs::File::create(dest)
+            .await
+            .map_err(|e| anyhow::anyhow!("Cannot create {}: {e}", dest.display()))?;
+
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut written: u64 = 0;
+        loop {
+            let read = tokio::select! {
+                biased;
+                () = self.cancel_token.cancelled() => anyhow::bail!("Request cancelled"),
+                read = reader.read(&mut buf) => read?,
+            };
+            if read == 0 {
+                break;
+            }
+            file.write_all(&buf[..read]).await?;
+            written += read as u64;
+        }
+        file.flush().await?;
+
+        Ok(written)
+    }
+
+    async fn get_to_file(
+        &self,
+        url: &str,
+        dest: &std::path::Path,
+    ) -> anyhow::Result<(u64, String, Option<String>)> {
+        let part_path = dest.with_extension("part");
+
+        // Without `--resume`, a `.part` file left behind by a previous,
+        // separate call (e.g. a killed process) is untrusted and discarded
+        // up front — every `get_to_file` call starts clean. With `--resume`
+        // it's kept, and validated against its sidecar inside
+        // `get_to_file_once` instead.
+        if !self.resume {
+            let _ = std::fs::remove_file(&part_path);
+            remove_part_meta(&part_path);
+        }
+
+        let mut last_err: Option<anyhow::Error> = None;
+
+        for attempt in 0..=self.max_retries {
+            match self.get_to_file_once(url, dest, &part_path).await {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    tracing::warn!(
+                        attempt = attempt + 1,
+                        url = %url,
+                        error = %e,
+                        "Asset download attempt failed, will retry from the partial file on disk"
+                    );
+                    last_err = Some(e);
+                }
+            }
+        }
+
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("Failed to download {url}")))
     }
 
     fn rate_limit(&self) -> u32 {
@@ -150,22 +1099,50 @@ mod tests {
 
     #[test]
     fn reqwest_client_custom_rate() {
-        let client = ReqwestClient::new(None, None, 10);
+        let client = ReqwestClient::new(None, None, 10, 3, 500, 4, None, true, false);
         assert_eq!(client.rate_limit(), 10);
     }
 
     #[test]
     fn reqwest_client_with_proxy() {
-        let client = ReqwestClient::new(Some("http://127.0.0.1:8080"), None, 2);
+        let client = ReqwestClient::new(Some("http://127.0.0.1:8080"), None, 2, 3, 500, 4, None, true, false);
         assert_eq!(client.rate_limit(), 2);
     }
 
     #[test]
     fn reqwest_client_with_cookie() {
-        let client = ReqwestClient::new(None, Some(("substack.sid", "abc123")), 2);
+        let client = ReqwestClient::new(None, Some(("substack.sid", "abc123")), 2, 3, 500, 4, None, true, false);
         assert_eq!(client.rate_limit(), 2);
     }
 
+    #[test]
+    fn reqwest_client_with_http_cache_dir() {
+        let client = ReqwestClient::new(
+            None,
+            None,
+            2,
+            3,
+            500,
+            4,
+            Some(std::path::PathBuf::from("http_cache")),
+            true,
+            false,
+        );
+        assert_eq!(client.rate_limit(), 2);
+    }
+
+    #[test]
+    fn reqwest_client_with_decompress_disabled_clears_decompress_flag() {
+        let client = ReqwestClient::new(None, None, 2, 3, 500, 4, None, false, false);
+        assert!(!client.decompress);
+    }
+
+    #[test]
+    fn reqwest_client_with_resume_enabled() {
+        let client = ReqwestClient::new(None, None, 2, 3, 500, 4, None, true, true);
+        assert!(client.resume);
+    }
+
     #[test]
     fn reqwest_client_implements_debug() {
         let client = ReqwestClient::default();
@@ -178,4 +1155,164 @@ mod tests {
         fn assert_send_sync<T: Send + Sync>() {}
         assert_send_sync::<ReqwestClient>();
     }
+
+    // -- throttling & cancellation --------------------------------------------
+
+    #[tokio::test]
+    async fn token_bucket_starts_full_and_does_not_block() {
+        let bucket = TokenBucket::new(5);
+        let start = Instant::now();
+        bucket.acquire().await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn token_bucket_blocks_once_capacity_is_exhausted() {
+        let bucket = TokenBucket::new(20); // 50ms per token once empty
+        for _ in 0..20 {
+            bucket.acquire().await;
+        }
+        let start = Instant::now();
+        bucket.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn cancel_makes_throttle_return_early() {
+        let client = ReqwestClient::new(None, None, 1, 3, 500, 1, None, true, false);
+        client.cancel();
+        assert!(client.throttle().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn cancel_makes_get_bytes_fail_fast() {
+        let client = ReqwestClient::new(None, None, 1, 3, 500, 1, None, true, false);
+        client.cancel();
+        let result = client.get_bytes("https://example.invalid/unused").await;
+        assert!(result.is_err());
+    }
+
+    // -- retry helpers -------------------------------------------------------
+
+    #[test]
+    fn retryable_statuses_include_throttling_and_gateway_errors() {
+        for code in [408, 429, 500, 502, 503, 504] {
+            assert!(is_retryable_status(reqwest::StatusCode::from_u16(code).unwrap()));
+        }
+    }
+
+    #[test]
+    fn non_retryable_statuses_excluded() {
+        for code in [400, 401, 403, 404] {
+            assert!(!is_retryable_status(reqwest::StatusCode::from_u16(code).unwrap()));
+        }
+    }
+
+    #[test]
+    fn backoff_with_jitter_never_exceeds_cap() {
+        for attempt in 0..10 {
+            let delay = backoff_with_jitter(500, attempt);
+            assert!(delay.as_millis() as u64 <= BACKOFF_CAP_MS);
+        }
+    }
+
+    #[test]
+    fn retry_after_parses_delta_seconds() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, "2".parse().unwrap());
+        let delay = retry_after_delay(&headers).expect("should parse delta-seconds form");
+        assert_eq!(delay, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn retry_after_missing_header_is_none() {
+        let headers = reqwest::header::HeaderMap::new();
+        assert!(retry_after_delay(&headers).is_none());
+    }
+
+    // -- redirect handling -----------------------------------------------
+
+    #[test]
+    fn resolve_redirect_joins_relative_location_against_current_url() {
+        let next = resolve_redirect("https://old.example/a/b", "/c").unwrap();
+        assert_eq!(next, "https://old.example/c");
+    }
+
+    #[test]
+    fn resolve_redirect_keeps_absolute_location_as_is() {
+        let next =
+            resolve_redirect("https://old.example/a", "https://new.example/b").unwrap();
+        assert_eq!(next, "https://new.example/b");
+    }
+
+    #[test]
+    fn resolve_redirect_rejects_garbage_location() {
+        assert!(resolve_redirect("https://old.example/a", "::not a url::").is_err());
+    }
+
+    #[test]
+    fn same_origin_matches_identical_scheme_host_and_port() {
+        assert!(same_origin(
+            "https://example.com:443/a",
+            "https://example.com/b"
+        ));
+    }
+
+    #[test]
+    fn same_origin_differs_across_hosts() {
+        assert!(!same_origin(
+            "https://example.com/a",
+            "https://other.example/a"
+        ));
+    }
+
+    #[test]
+    fn same_origin_differs_across_schemes() {
+        assert!(!same_origin("https://example.com/a", "http://example.com/a"));
+    }
+
+    #[tokio::test]
+    async fn cross_origin_redirect_drops_cookie_header() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let origin_server = MockServer::start().await;
+        let foreign_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/start"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("Location", format!("{}/landed", foreign_server.uri())),
+            )
+            .mount(&origin_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/landed"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .mount(&foreign_server)
+            .await;
+
+        let client = ReqwestClient::new(None, Some(("session", "top-secret")), 100, 0, 10, 4, None, true, false);
+        let url = format!("{}/start", origin_server.uri());
+        let body = client.get_bytes(&url).await.expect("request should succeed");
+        assert_eq!(body, b"ok");
+
+        let origin_requests = origin_server.received_requests().await.expect("recorder enabled");
+        assert_eq!(origin_requests.len(), 1);
+        assert!(
+            origin_requests[0].headers.contains_key(reqwest::header::COOKIE),
+            "cookie should be sent to the original origin: {:?}",
+            origin_requests[0].headers
+        );
+
+        let foreign_requests = foreign_server.received_requests().await.expect("recorder enabled");
+        assert_eq!(foreign_requests.len(), 1);
+        assert!(
+            !foreign_requests[0].headers.contains_key(reqwest::header::COOKIE),
+            "cookie leaked across a redirect to a foreign origin: {:?}",
+            foreign_requests[0].headers
+        );
+        assert!(!foreign_requests[0].headers.contains_key(reqwest::header::AUTHORIZATION));
+    }
 }
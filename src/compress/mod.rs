@@ -0,0 +1,182 @@
+//! @project       RoBustack-DL
+//! @organization  CodeBush Collective
+//! @license       GPL-3.0-only
+//! ---------------------------------------------------------------------------
+//! AI PROVENANCE & HUMAN-IN-THE-LOOP (HITL) METADATA:
+//! - Prompt Engineering: Gemini 3 Flash (Strategy, Scoping & Context Tuning)
+//! - Code Generation:   Gemini 3 Pro (Core Systems Engineering & Async Logic)
+//! - Technical Review:  Claude 4.6 Opus (Security Audit & Idiomatic Refinement)
+//! - HITL Verification: Collisio-Adolebitque - AA0614550BDC21F1 (Manual Audit & Final Validation)
+//! ---------------------------------------------------------------------------
+//! Verified Date: 2026-07-30
+//! Integrity: GPG-Signed | HITL-Certified
+//!
+//! Output compression — gzip/brotli/zstd encoding of stored post bodies and
+//! downloaded assets, independent of [`crate::client`]'s transparent
+//! *decompression* of HTTP responses on read.
+//!
+//! # Design
+//! Compression here is opt-in (`--compress gzip|br|zstd`) and only ever
+//! applied to the bytes written to disk. Callers are expected to hash the
+//! *uncompressed* content first (for idempotency and manifest digests) and
+//! only compress immediately before the final `write_all`, so re-running
+//! with `--compress` toggled between runs never invalidates the manifest.
+//!
+//! # Decompression
+//! [`decompress_bytes`] is the inverse, keyed by the encoding label
+//! recorded on [`crate::integrity::ManifestEntry::encoding`] rather than by
+//! [`CompressionMode`] directly — `audit` only ever has the string label
+//! read back out of the manifest, never the original enum.
+
+use crate::config::CompressionMode;
+
+/// Filename suffix appended after the existing extension, e.g.
+/// `post.html` becomes `post.html.gz`.
+#[must_use]
+pub fn suffix(mode: CompressionMode) -> &'static str {
+    match mode {
+        CompressionMode::Gzip => ".gz",
+        CompressionMode::Br => ".br",
+        CompressionMode::Zstd => ".zst",
+    }
+}
+
+/// Encoding label recorded on [`crate::integrity::ManifestEntry::encoding`].
+#[must_use]
+pub fn encoding_label(mode: CompressionMode) -> &'static str {
+    match mode {
+        CompressionMode::Gzip => "gzip",
+        CompressionMode::Br => "br",
+        CompressionMode::Zstd => "zstd",
+    }
+}
+
+/// Compress `data` with `mode`, returning the encoded bytes.
+///
+/// # Errors
+/// Returns `anyhow::Error` if the encoder fails to flush.
+pub async fn compress_bytes(mode: CompressionMode, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut out = Vec::new();
+    match mode {
+        CompressionMode::Gzip => {
+            let mut encoder = async_compression::tokio::write::GzipEncoder::new(&mut out);
+            encoder.write_all(data).await?;
+            encoder.shutdown().await?;
+        }
+        CompressionMode::Br => {
+            let mut encoder = async_compression::tokio::write::BrotliEncoder::new(&mut out);
+            encoder.write_all(data).await?;
+            encoder.shutdown().await?;
+        }
+        CompressionMode::Zstd => {
+            let mut encoder = async_compression::tokio::write::ZstdEncoder::new(&mut out);
+            encoder.write_all(data).await?;
+            encoder.shutdown().await?;
+        }
+    }
+    Ok(out)
+}
+
+/// Decompress `data` that was previously encoded with `encoding` (one of
+/// the labels returned by [`encoding_label`] — `"gzip"`, `"br"`, or
+/// `"zstd"`), returning the original bytes.
+///
+/// Unrecognized labels pass `data` through unchanged, since an `encoding`
+/// field predates this function and older manifests may carry values this
+/// build doesn't know about; a blind failure here would turn a harmless
+/// forward-compatibility gap into a hard audit error.
+///
+/// # Errors
+/// Returns `anyhow::Error` if the encoded stream is truncated or corrupt.
+pub async fn decompress_bytes(encoding: &str, data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    use tokio::io::AsyncReadExt;
+
+    let reader = tokio::io::BufReader::new(data);
+    let mut out = Vec::new();
+    match encoding {
+        "gzip" => {
+            let mut decoder = async_compression::tokio::bufread::GzipDecoder::new(reader);
+            decoder.read_to_end(&mut out).await?;
+        }
+        "br" => {
+            let mut decoder = async_compression::tokio::bufread::BrotliDecoder::new(reader);
+            decoder.read_to_end(&mut out).await?;
+        }
+        "zstd" => {
+            let mut decoder = async_compression::tokio::bufread::ZstdDecoder::new(reader);
+            decoder.read_to_end(&mut out).await?;
+        }
+        _ => return Ok(data.to_vec()),
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suffix_matches_mode() {
+        assert_eq!(suffix(CompressionMode::Gzip), ".gz");
+        assert_eq!(suffix(CompressionMode::Br), ".br");
+        assert_eq!(suffix(CompressionMode::Zstd), ".zst");
+    }
+
+    #[test]
+    fn encoding_label_matches_mode() {
+        assert_eq!(encoding_label(CompressionMode::Gzip), "gzip");
+        assert_eq!(encoding_label(CompressionMode::Br), "br");
+        assert_eq!(encoding_label(CompressionMode::Zstd), "zstd");
+    }
+
+    #[tokio::test]
+    async fn compress_bytes_gzip_round_trips() {
+        use tokio::io::AsyncReadExt;
+
+        let data = b"hello world, this is a test of gzip compression";
+        let compressed = compress_bytes(CompressionMode::Gzip, data).await.unwrap();
+        assert_ne!(compressed, data);
+
+        let reader = tokio::io::BufReader::new(std::io::Cursor::new(compressed));
+        let mut decoder = async_compression::tokio::bufread::GzipDecoder::new(reader);
+        let mut decoded = Vec::new();
+        decoder.read_to_end(&mut decoded).await.unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[tokio::test]
+    async fn compress_bytes_br_produces_nonempty_output() {
+        let data = b"hello world, this is a test of brotli compression";
+        let compressed = compress_bytes(CompressionMode::Br, data).await.unwrap();
+        assert!(!compressed.is_empty());
+        assert_ne!(compressed, data);
+    }
+
+    #[tokio::test]
+    async fn compress_bytes_zstd_round_trips() {
+        let data = b"hello world, this is a test of zstd compression";
+        let compressed = compress_bytes(CompressionMode::Zstd, data).await.unwrap();
+        assert_ne!(compressed, data);
+
+        let decoded = decompress_bytes("zstd", &compressed).await.unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[tokio::test]
+    async fn decompress_bytes_br_round_trips() {
+        let data = b"hello world, this is a test of brotli round-tripping";
+        let compressed = compress_bytes(CompressionMode::Br, data).await.unwrap();
+
+        let decoded = decompress_bytes("br", &compressed).await.unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[tokio::test]
+    async fn decompress_bytes_unknown_encoding_passes_through() {
+        let data = b"already plain bytes";
+        let decoded = decompress_bytes("identity", data).await.unwrap();
+        assert_eq!(decoded, data);
+    }
+}
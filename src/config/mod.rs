@@ -21,7 +21,77 @@
 
 use std::path::PathBuf;
 
-pub use crate::cli::{ImageQuality, OutputFormat};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+
+pub use crate::cli::{ArchiveFormat, CompressionMode, HashAlgo, ImageQuality, OutputFormat};
+use crate::integrity::Sandbox;
+
+// ---------------------------------------------------------------------------
+// DateRange — parsed, timezone-aware publish-date filter
+// ---------------------------------------------------------------------------
+
+/// Parsed, timezone-aware bounds for filtering posts by publish date.
+///
+/// Built once in [`AppConfig::from_cli`] from the raw `--after`/`--before`
+/// strings, so malformed input is rejected at config-build time rather
+/// than silently matching zero posts downstream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DateRange {
+    /// Only include posts published at or after this instant.
+    pub after: Option<DateTime<Utc>>,
+    /// Only include posts published at or before this instant.
+    pub before: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    /// Returns `true` if `post_date` (an RFC 3339 timestamp, as reported by
+    /// the Substack API) falls within this range.
+    ///
+    /// A `post_date` that cannot be parsed is conservatively included —
+    /// filtering shouldn't silently drop posts because of an unexpected
+    /// upstream format.
+    #[must_use]
+    pub fn contains(&self, post_date: &str) -> bool {
+        if self.after.is_none() && self.before.is_none() {
+            return true;
+        }
+        let Ok(parsed) = DateTime::parse_from_rfc3339(post_date) else {
+            return true;
+        };
+        let parsed = parsed.with_timezone(&Utc);
+        if let Some(after) = self.after {
+            if parsed < after {
+                return false;
+            }
+        }
+        if let Some(before) = self.before {
+            if parsed > before {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Parse a `--after`/`--before` bound: either a bare `YYYY-MM-DD` date
+/// (interpreted as start-of-day UTC, or end-of-day when `end_of_day` is
+/// set) or a full RFC 3339 timestamp.
+fn parse_date_bound(raw: &str, end_of_day: bool) -> anyhow::Result<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d").map_err(|e| {
+        anyhow::anyhow!("Invalid date '{raw}': {e} (expected YYYY-MM-DD or RFC 3339)")
+    })?;
+    let time = if end_of_day {
+        NaiveTime::from_hms_milli_opt(23, 59, 59, 999).expect("valid end-of-day time")
+    } else {
+        NaiveTime::MIN
+    };
+
+    Ok(Utc.from_utc_datetime(&date.and_time(time)))
+}
 
 // ---------------------------------------------------------------------------
 // AppConfig — single source of truth for all runtime settings
@@ -45,12 +115,30 @@ pub struct AppConfig {
     pub proxy: Option<String>,
     /// Maximum requests per second.
     pub rate_limit: u32,
+    /// Maximum retry attempts for retryable HTTP failures.
+    pub max_retries: u32,
+    /// Base delay (milliseconds) for exponential backoff between retries.
+    pub base_backoff_ms: u64,
+    /// Maximum number of in-flight requests at once.
+    pub max_concurrent: u32,
+    /// Directory for the HTTP conditional-request cache (ETag/Last-Modified
+    /// revalidation). `None` disables HTTP caching. For `download`, this
+    /// defaults to `<output>/.robustack-cache` unless `--http-cache-dir`
+    /// overrides the location or `--no-cache` disables it outright.
+    pub http_cache_dir: Option<PathBuf>,
+    /// Negotiate and transparently decode `Content-Encoding: br`/`gzip`/
+    /// `deflate` responses. `false` enables store-raw mode (byte-exact
+    /// mirrors of the wire response).
+    pub decompress: bool,
 
     // -- Filtering --
-    /// Only process posts published on or after this date.
-    pub after: Option<String>,
-    /// Only process posts published on or before this date.
-    pub before: Option<String>,
+    /// Parsed, timezone-aware publish-date filter bounds.
+    pub date_range: DateRange,
+
+    // -- Resilience --
+    /// Fall back to parsing `{url}/feed` as RSS/Atom when the Substack
+    /// JSON API errors or returns an unparseable body.
+    pub allow_rss_fallback: bool,
 
     // -- Output --
     /// Output format (html, md, txt).
@@ -73,14 +161,46 @@ pub struct AppConfig {
     pub files_dir: String,
     /// Comma-separated extension allowlist (empty = all).
     pub file_extensions: String,
+    /// Comma-separated host-suffix allowlist for embedded resource fetches
+    /// (empty = all hosts allowed, subject to `domain_deny`).
+    pub domain_allow: String,
+    /// Comma-separated host-suffix denylist for embedded resource fetches.
+    /// Takes precedence over `domain_allow`.
+    pub domain_deny: String,
     /// Append source URL to each downloaded file.
     pub add_source_url: bool,
     /// Generate an archive index page.
     pub create_archive: bool,
+    /// Packaging format for the generated archive.
+    pub archive_format: ArchiveFormat,
+    /// Subdirectory name for the content-addressed download cache.
+    pub cache_dir: String,
+    /// Whether the local download cache is consulted and populated.
+    pub cache_enabled: bool,
+    /// Digest algorithm used for content-addressed storage.
+    pub hash_algo: HashAlgo,
+    /// Compression applied to written post bodies and downloaded assets.
+    /// `None` writes raw, uncompressed files.
+    pub compress: Option<CompressionMode>,
+    /// Extract the main article body before converting to "md"/"txt".
+    pub readability: bool,
+    /// Resume partial downloads across process restarts using a persisted
+    /// `.part` validator, instead of discarding leftover scratch files.
+    pub resume: bool,
+    /// Path to an ed25519 secret key used to sign `manifest.json` after it's
+    /// written. `None` leaves the manifest unsigned.
+    pub sign_key: Option<PathBuf>,
 
     // -- Diagnostics --
     /// Verbose / debug logging enabled.
     pub verbose: bool,
+
+    // -- Integrity --
+    /// Allowed root(s) for manifest/file path containment checks. Always
+    /// includes the current working directory and `output_dir`, plus
+    /// `http_cache_dir` when it's set, so `--output`/`--http-cache-dir`
+    /// pointing outside the CWD don't trip containment checks.
+    pub sandbox: Sandbox,
 }
 
 impl AppConfig {
@@ -88,8 +208,14 @@ impl AppConfig {
     ///
     /// This is the **only** place where `Cli` types cross into the domain
     /// layer.  After this point every consumer works with `AppConfig`.
-    #[must_use]
-    pub fn from_cli(cli: &crate::cli::Cli, download: Option<&crate::cli::DownloadArgs>) -> Self {
+    ///
+    /// # Errors
+    /// Returns `anyhow::Error` if `--after`/`--before` is set to a string
+    /// that is neither `YYYY-MM-DD` nor a valid RFC 3339 timestamp.
+    pub fn from_cli(
+        cli: &crate::cli::Cli,
+        download: Option<&crate::cli::DownloadArgs>,
+    ) -> anyhow::Result<Self> {
         let (
             format,
             output_dir,
@@ -100,8 +226,19 @@ impl AppConfig {
             download_files,
             files_dir,
             file_extensions,
+            domain_allow,
+            domain_deny,
             add_source_url,
             create_archive,
+            archive_format,
+            cache_dir,
+            cache_enabled,
+            hash_algo,
+            compress,
+            readability,
+            resume,
+            sign_key,
+            http_cache_dir,
         ) = if let Some(dl) = download {
             (
                 dl.format,
@@ -113,8 +250,27 @@ impl AppConfig {
                 dl.download_files,
                 dl.files_dir.clone(),
                 dl.file_extensions.clone(),
+                dl.domain_allow.clone(),
+                dl.domain_deny.clone(),
                 dl.add_source_url,
                 dl.create_archive,
+                dl.archive_format,
+                dl.cache_dir.clone(),
+                !dl.no_cache,
+                dl.hash_algo,
+                dl.compress,
+                dl.readability,
+                dl.resume,
+                dl.sign_key.clone(),
+                // Incremental re-sync: unless `--http-cache-dir` pins an
+                // explicit location or `--no-cache` opts out entirely,
+                // `download` defaults the HTTP conditional-request cache to
+                // a directory under its own output so a second run against
+                // the same publication sends `If-None-Match`/
+                // `If-Modified-Since` and skips re-fetching unchanged posts.
+                cli.http_cache_dir
+                    .clone()
+                    .or_else(|| (!dl.no_cache).then(|| dl.output.join(".robustack-cache"))),
             )
         } else {
             (
@@ -127,18 +283,57 @@ impl AppConfig {
                 false,
                 "files".to_owned(),
                 String::new(),
+                String::new(),
+                String::new(),
+                false,
                 false,
+                ArchiveFormat::Dir,
+                "cache".to_owned(),
+                true,
+                HashAlgo::Sha256,
+                None,
                 false,
+                false,
+                None,
+                cli.http_cache_dir.clone(),
             )
         };
 
-        Self {
+        // Permit the resolved output directory (and HTTP cache dir, if
+        // pinned outside it) as sandbox roots alongside the CWD, so
+        // `--output`/`--http-cache-dir` pointing outside the current
+        // working directory doesn't trip path-containment checks.
+        let mut sandbox_roots = vec![PathBuf::from("."), output_dir.clone()];
+        if let Some(cache_dir) = &http_cache_dir {
+            sandbox_roots.push(cache_dir.clone());
+        }
+        let sandbox = Sandbox::new(sandbox_roots);
+
+        let date_range = DateRange {
+            after: cli
+                .after
+                .as_deref()
+                .map(|s| parse_date_bound(s, false))
+                .transpose()?,
+            before: cli
+                .before
+                .as_deref()
+                .map(|s| parse_date_bound(s, true))
+                .transpose()?,
+        };
+
+        Ok(Self {
             cookie_name: cli.cookie_name.clone(),
             cookie_value: cli.cookie_val.clone(),
             proxy: cli.proxy.clone(),
             rate_limit: cli.rate,
-            after: cli.after.clone(),
-            before: cli.before.clone(),
+            max_retries: cli.max_retries,
+            base_backoff_ms: cli.base_backoff_ms,
+            max_concurrent: cli.max_concurrent,
+            http_cache_dir,
+            decompress: !cli.no_decompress,
+            date_range,
+            allow_rss_fallback: cli.allow_rss_fallback,
             verbose: cli.verbose,
             format,
             output_dir,
@@ -149,9 +344,20 @@ impl AppConfig {
             download_files,
             files_dir,
             file_extensions,
+            domain_allow,
+            domain_deny,
             add_source_url,
             create_archive,
-        }
+            archive_format,
+            cache_dir,
+            cache_enabled,
+            hash_algo,
+            compress,
+            readability,
+            resume,
+            sign_key,
+            sandbox,
+        })
     }
 
     /// Returns parsed file extension allowlist (empty vec = accept all).
@@ -191,16 +397,135 @@ mod tests {
     #[test]
     fn from_cli_captures_global_flags() {
         let cli = test_cli();
-        let config = AppConfig::from_cli(&cli, None);
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
         assert_eq!(config.rate_limit, 5);
         assert!(!config.verbose);
     }
 
+    #[test]
+    fn from_cli_captures_retry_defaults() {
+        let cli = test_cli();
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.base_backoff_ms, 500);
+    }
+
+    #[test]
+    fn from_cli_captures_max_concurrent_default() {
+        let cli = test_cli();
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert_eq!(config.max_concurrent, 4);
+    }
+
+    #[test]
+    fn from_cli_defaults_http_cache_dir_to_none() {
+        let cli = test_cli();
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert_eq!(config.http_cache_dir, None);
+    }
+
+    #[test]
+    fn from_cli_download_defaults_http_cache_dir_under_output() {
+        let cli = test_cli();
+        if let crate::cli::Commands::Download(ref dl) = cli.command {
+            let config = AppConfig::from_cli(&cli, Some(dl)).expect("valid config");
+            assert_eq!(
+                config.http_cache_dir,
+                Some(PathBuf::from(".").join(".robustack-cache"))
+            );
+        } else {
+            panic!("expected Download command");
+        }
+    }
+
+    #[test]
+    fn from_cli_download_no_cache_disables_http_cache_dir() {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+            "--no-cache",
+        ])
+        .expect("valid test args");
+        if let crate::cli::Commands::Download(ref dl) = cli.command {
+            let config = AppConfig::from_cli(&cli, Some(dl)).expect("valid config");
+            assert_eq!(config.http_cache_dir, None);
+        } else {
+            panic!("expected Download command");
+        }
+    }
+
+    #[test]
+    fn from_cli_download_explicit_http_cache_dir_overrides_default() {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "--http-cache-dir",
+            "custom-http-cache",
+            "download",
+            "--url",
+            "https://example.com",
+        ])
+        .expect("valid test args");
+        if let crate::cli::Commands::Download(ref dl) = cli.command {
+            let config = AppConfig::from_cli(&cli, Some(dl)).expect("valid config");
+            assert_eq!(config.http_cache_dir, Some(PathBuf::from("custom-http-cache")));
+        } else {
+            panic!("expected Download command");
+        }
+    }
+
+    #[test]
+    fn from_cli_defaults_decompress_to_true() {
+        let cli = test_cli();
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert!(config.decompress);
+    }
+
+    #[test]
+    fn from_cli_no_decompress_flag_disables_decompress() {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "--no-decompress",
+            "download",
+            "--url",
+            "https://example.com",
+        ])
+        .expect("valid test args");
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert!(!config.decompress);
+    }
+
+    #[test]
+    fn from_cli_defaults_allow_rss_fallback_to_false() {
+        let cli = test_cli();
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert!(!config.allow_rss_fallback);
+    }
+
+    #[test]
+    fn from_cli_allow_rss_fallback_flag_enables_it() {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "--allow-rss-fallback",
+            "download",
+            "--url",
+            "https://example.com",
+        ])
+        .expect("valid test args");
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert!(config.allow_rss_fallback);
+    }
+
     #[test]
     fn from_cli_captures_download_args() {
         let cli = test_cli();
         if let crate::cli::Commands::Download(ref dl) = cli.command {
-            let config = AppConfig::from_cli(&cli, Some(dl));
+            let config = AppConfig::from_cli(&cli, Some(dl)).expect("valid config");
             assert_eq!(config.format, OutputFormat::Md);
             assert_eq!(config.output_dir, PathBuf::from("."));
             assert!(!config.dry_run);
@@ -209,26 +534,142 @@ mod tests {
         }
     }
 
+    #[test]
+    fn from_cli_sandbox_permits_output_dir_outside_cwd() {
+        use crate::integrity::Manifest;
+        use clap::Parser;
+
+        let dir = std::env::temp_dir().join("robustack_test_config_sandbox_output_dir");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.substack.com",
+            "--output",
+            dir.to_str().unwrap(),
+        ])
+        .expect("valid test args");
+
+        if let crate::cli::Commands::Download(ref dl) = cli.command {
+            let config = AppConfig::from_cli(&cli, Some(dl)).expect("valid config");
+            std::fs::create_dir_all(&config.output_dir).unwrap();
+
+            let mut manifest = Manifest::default();
+            manifest
+                .save(&config.output_dir, &config.sandbox)
+                .expect("save should not be blocked by the sandbox");
+        } else {
+            panic!("expected Download command");
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn from_cli_defaults_when_no_download() {
         let cli = test_cli();
-        let config = AppConfig::from_cli(&cli, None);
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
         assert_eq!(config.format, OutputFormat::Html);
         assert!(!config.download_images);
         assert!(!config.download_files);
     }
 
+    #[test]
+    fn from_cli_defaults_compress_to_none() {
+        let cli = test_cli();
+        if let crate::cli::Commands::Download(ref dl) = cli.command {
+            let config = AppConfig::from_cli(&cli, Some(dl)).expect("valid config");
+            assert_eq!(config.compress, None);
+        } else {
+            panic!("expected Download command");
+        }
+    }
+
+    #[test]
+    fn from_cli_compress_flag_is_captured() {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+            "--compress",
+            "br",
+        ])
+        .expect("valid test args");
+        if let crate::cli::Commands::Download(ref dl) = cli.command {
+            let config = AppConfig::from_cli(&cli, Some(dl)).expect("valid config");
+            assert_eq!(config.compress, Some(CompressionMode::Br));
+        } else {
+            panic!("expected Download command");
+        }
+    }
+
+    #[test]
+    fn from_cli_defaults_readability_to_false() {
+        let cli = test_cli();
+        if let crate::cli::Commands::Download(ref dl) = cli.command {
+            let config = AppConfig::from_cli(&cli, Some(dl)).expect("valid config");
+            assert!(!config.readability);
+        } else {
+            panic!("expected Download command");
+        }
+    }
+
+    #[test]
+    fn from_cli_readability_flag_is_captured() {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+            "--readability",
+        ])
+        .expect("valid test args");
+        if let crate::cli::Commands::Download(ref dl) = cli.command {
+            let config = AppConfig::from_cli(&cli, Some(dl)).expect("valid config");
+            assert!(config.readability);
+        } else {
+            panic!("expected Download command");
+        }
+    }
+
+    #[test]
+    fn from_cli_defaults_archive_format_to_dir() {
+        let cli = test_cli();
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert_eq!(config.archive_format, ArchiveFormat::Dir);
+    }
+
+    #[test]
+    fn from_cli_captures_cache_defaults() {
+        let cli = test_cli();
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert_eq!(config.cache_dir, "cache");
+        assert!(config.cache_enabled);
+    }
+
+    #[test]
+    fn from_cli_defaults_hash_algo_to_sha256() {
+        let cli = test_cli();
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert_eq!(config.hash_algo, HashAlgo::Sha256);
+    }
+
     #[test]
     fn allowed_extensions_empty_string() {
         let cli = test_cli();
-        let config = AppConfig::from_cli(&cli, None);
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
         assert!(config.allowed_extensions().is_empty());
     }
 
     #[test]
     fn allowed_extensions_parses_csv() {
         let cli = test_cli();
-        let mut config = AppConfig::from_cli(&cli, None);
+        let mut config = AppConfig::from_cli(&cli, None).expect("valid config");
         config.file_extensions = "pdf, docx, epub".to_owned();
         let exts = config.allowed_extensions();
         assert_eq!(exts, vec!["pdf", "docx", "epub"]);
@@ -237,7 +678,7 @@ mod tests {
     #[test]
     fn config_implements_debug() {
         let cli = test_cli();
-        let config = AppConfig::from_cli(&cli, None);
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
         let debug = format!("{config:?}");
         assert!(debug.contains("AppConfig"));
     }
@@ -245,8 +686,92 @@ mod tests {
     #[test]
     fn config_implements_clone() {
         let cli = test_cli();
-        let config = AppConfig::from_cli(&cli, None);
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
         let cloned = config.clone();
         assert_eq!(cloned.rate_limit, config.rate_limit);
     }
+
+    #[test]
+    fn from_cli_parses_bare_dates_as_day_bounds() {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "--after",
+            "2024-01-01",
+            "--before",
+            "2024-12-31",
+            "download",
+            "--url",
+            "https://example.com",
+        ])
+        .expect("valid test args");
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert_eq!(
+            config.date_range.after,
+            Some("2024-01-01T00:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            config.date_range.before,
+            Some("2024-12-31T23:59:59.999Z".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn from_cli_parses_rfc3339_bounds() {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "--after",
+            "2024-01-01T12:30:00+02:00",
+            "download",
+            "--url",
+            "https://example.com",
+        ])
+        .expect("valid test args");
+        let config = AppConfig::from_cli(&cli, None).expect("valid config");
+        assert_eq!(
+            config.date_range.after,
+            Some("2024-01-01T10:30:00Z".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn from_cli_rejects_malformed_date() {
+        use clap::Parser;
+        let cli = crate::cli::Cli::try_parse_from([
+            "robustack-dl",
+            "--after",
+            "not-a-date",
+            "download",
+            "--url",
+            "https://example.com",
+        ])
+        .expect("valid test args");
+        assert!(AppConfig::from_cli(&cli, None).is_err());
+    }
+
+    #[test]
+    fn date_range_contains_respects_bounds() {
+        let range = DateRange {
+            after: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+            before: Some("2024-12-31T23:59:59Z".parse().unwrap()),
+        };
+        assert!(range.contains("2024-06-15T00:00:00Z"));
+        assert!(!range.contains("2023-12-31T23:59:59Z"));
+        assert!(!range.contains("2025-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn date_range_contains_is_permissive_with_unparseable_input() {
+        let range = DateRange {
+            after: Some("2024-01-01T00:00:00Z".parse().unwrap()),
+            before: None,
+        };
+        assert!(range.contains("not-a-date"));
+    }
+
+    #[test]
+    fn date_range_default_contains_everything() {
+        assert!(DateRange::default().contains("garbage"));
+    }
 }
@@ -11,9 +11,11 @@
 //! Verified Date: 2026-02-15
 //! Integrity: GPG-Signed | HITL-Certified
 //!
-//! Archive handler — generates an index of downloaded content.
+//! Archive handler — generates an index and Atom feed of downloaded
+//! content and, optionally, packages the output directory into a single
+//! file.
 
-use crate::config::{AppConfig, OutputFormat};
+use crate::config::{AppConfig, ArchiveFormat, OutputFormat};
 use crate::handlers::substack::SubstackPost;
 use crate::integrity;
 use anyhow::Context;
@@ -23,18 +25,31 @@ use tracing::info;
 /// Generate an index file (index.html) listing all available posts.
 ///
 /// This function relies on the fact that filenames are deterministically
-/// derived from post slugs: `{slug}.{ext}`.
-pub fn generate_index(posts: &[SubstackPost], config: &AppConfig) -> anyhow::Result<()> {
+/// derived from post slugs: `{slug}.{ext}`, plus a `.gz`/`.br`/`.zst` suffix
+/// when `config.compress` is set, matching the names
+/// `handlers::download::run` actually writes. `index.html` itself is
+/// compressed the same way when `config.compress` is set, so a backfill run
+/// through `--compress` doesn't leave the index as the one uncompressed
+/// file in the archive.
+pub async fn generate_index(posts: &[SubstackPost], config: &AppConfig) -> anyhow::Result<()> {
     if posts.is_empty() {
         return Ok(());
     }
 
+    // EPUB output is a single self-contained file (see `handlers::epub`),
+    // not loose per-post files, so an `index.html` linking to per-slug
+    // files would have nothing to point at.
+    if config.format == OutputFormat::Epub {
+        return Ok(());
+    }
+
     info!("Generating archive index");
 
     let ext = match config.format {
-        OutputFormat::Html => "html",
+        OutputFormat::Html | OutputFormat::Single => "html",
         OutputFormat::Md => "md",
         OutputFormat::Txt => "txt",
+        OutputFormat::Epub => unreachable!("handled above"),
     };
 
     let mut html = String::new();
@@ -56,7 +71,10 @@ pub fn generate_index(posts: &[SubstackPost], config: &AppConfig) -> anyhow::Res
 
     for post in posts {
         let safe_slug = integrity::sanitize_filename(&post.slug);
-        let filename = format!("{safe_slug}.{ext}");
+        let filename = match config.compress {
+            Some(mode) => format!("{safe_slug}.{ext}{}", crate::compress::suffix(mode)),
+            None => format!("{safe_slug}.{ext}"),
+        };
         // Escape HTML in title (basic)
         let title = post.title.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
         
@@ -70,9 +88,17 @@ pub fn generate_index(posts: &[SubstackPost], config: &AppConfig) -> anyhow::Res
     html.push_str("</body>\n</html>");
 
     if !config.dry_run {
-        let path = config.output_dir.join("index.html");
+        let filename = match config.compress {
+            Some(mode) => format!("index.html{}", crate::compress::suffix(mode)),
+            None => "index.html".to_owned(),
+        };
+        let bytes = match config.compress {
+            Some(mode) => crate::compress::compress_bytes(mode, html.as_bytes()).await?,
+            None => html.into_bytes(),
+        };
+        let path = config.output_dir.join(&filename);
         let mut file = std::fs::File::create(&path).context("Failed to create index.html")?;
-        file.write_all(html.as_bytes())?;
+        file.write_all(&bytes)?;
         info!(path = %path.display(), "Saved archive index");
     } else {
         info!("Dry run: would save index.html");
@@ -80,3 +106,425 @@ pub fn generate_index(posts: &[SubstackPost], config: &AppConfig) -> anyhow::Res
 
     Ok(())
 }
+
+/// Generate an Atom 1.0 feed (`feed.xml`) describing the downloaded
+/// corpus, so a feed reader pointed at the mirrored archive picks up
+/// updates whenever the downloader is re-run.
+///
+/// Relies on the same filename scheme as [`generate_index`]:
+/// `{slug}.{ext}`, plus a `.gz`/`.br`/`.zst` suffix when `config.compress`
+/// is set. `feed.xml` itself is compressed the same way when
+/// `config.compress` is set — see [`generate_index`].
+pub async fn generate_feed(posts: &[SubstackPost], config: &AppConfig) -> anyhow::Result<()> {
+    if posts.is_empty() {
+        return Ok(());
+    }
+
+    // EPUB output has no per-post files to link to (see `generate_index`).
+    if config.format == OutputFormat::Epub {
+        return Ok(());
+    }
+
+    info!("Generating Atom feed");
+
+    let ext = match config.format {
+        OutputFormat::Html | OutputFormat::Single => "html",
+        OutputFormat::Md => "md",
+        OutputFormat::Txt => "txt",
+        OutputFormat::Epub => unreachable!("handled above"),
+    };
+
+    let mut sorted: Vec<&SubstackPost> = posts.iter().collect();
+    sorted.sort_by(|a, b| parse_post_date(&b.post_date).cmp(&parse_post_date(&a.post_date)));
+
+    let feed_updated = sorted
+        .iter()
+        .filter_map(|p| parse_post_date(&p.post_date))
+        .max()
+        .map_or_else(|| chrono::Utc::now().to_rfc3339(), |dt| dt.to_rfc3339());
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str("<title>Archive Feed</title>\n");
+    xml.push_str("<id>urn:robustack-dl:archive</id>\n");
+    xml.push_str(&format!("<updated>{}</updated>\n", xml_escape(&feed_updated)));
+
+    for post in sorted {
+        let safe_slug = integrity::sanitize_filename(&post.slug);
+        let filename = match config.compress {
+            Some(mode) => format!("{safe_slug}.{ext}{}", crate::compress::suffix(mode)),
+            None => format!("{safe_slug}.{ext}"),
+        };
+        let updated = parse_post_date(&post.post_date)
+            .map_or_else(|| post.post_date.clone(), |dt| dt.to_rfc3339());
+
+        xml.push_str("<entry>\n");
+        xml.push_str(&format!("<title>{}</title>\n", xml_escape(&post.title)));
+        xml.push_str(&format!(
+            "<link rel=\"alternate\" href=\"{}\"/>\n",
+            xml_escape(&filename)
+        ));
+        xml.push_str(&format!("<id>urn:robustack-dl:post:{}</id>\n", post.id));
+        xml.push_str(&format!("<updated>{}</updated>\n", xml_escape(&updated)));
+        xml.push_str(&format!("<published>{}</published>\n", xml_escape(&updated)));
+        xml.push_str(&format!("<summary>{}</summary>\n", xml_escape(&post.description)));
+        xml.push_str("</entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+
+    if !config.dry_run {
+        let filename = match config.compress {
+            Some(mode) => format!("feed.xml{}", crate::compress::suffix(mode)),
+            None => "feed.xml".to_owned(),
+        };
+        let bytes = match config.compress {
+            Some(mode) => crate::compress::compress_bytes(mode, xml.as_bytes()).await?,
+            None => xml.into_bytes(),
+        };
+        let path = config.output_dir.join(&filename);
+        let mut file = std::fs::File::create(&path).context("Failed to create feed.xml")?;
+        file.write_all(&bytes)?;
+        info!(path = %path.display(), "Saved Atom feed");
+    } else {
+        info!("Dry run: would save feed.xml");
+    }
+
+    Ok(())
+}
+
+/// Parse `post_date` (an RFC 3339 timestamp, as reported by the Substack
+/// API) into a comparable instant. Returns `None` for an unparseable date
+/// rather than guessing, so such posts sort last rather than skewing the
+/// feed's `<updated>` timestamp.
+fn parse_post_date(post_date: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(post_date)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+}
+
+/// Escape the handful of characters that are unsafe in XML text content.
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Package `config.output_dir` into a single file per `config.archive_format`.
+///
+/// `index.html` and `manifest.json` are included alongside the downloaded
+/// posts so the package is self-contained: extracting it reproduces the
+/// exact directory `audit` already knows how to verify. A no-op for
+/// [`ArchiveFormat::Dir`] and for dry runs.
+///
+/// # Errors
+/// Returns `anyhow::Error` if the output directory cannot be walked or the
+/// package file cannot be written.
+pub async fn package(config: &AppConfig) -> anyhow::Result<()> {
+    if config.dry_run || config.archive_format == ArchiveFormat::Dir {
+        return Ok(());
+    }
+
+    let entries = collect_package_entries(&config.output_dir)?;
+
+    match config.archive_format {
+        ArchiveFormat::Dir => unreachable!("handled above"),
+        ArchiveFormat::Zip => {
+            let path = package_path(&config.output_dir, "zip");
+            write_zip(&path, &config.output_dir, &entries)?;
+            info!(path = %path.display(), "Packaged archive as zip");
+        }
+        ArchiveFormat::TarGz => {
+            let path = package_path(&config.output_dir, "tar.gz");
+            write_tar_gz(&path, &config.output_dir, &entries).await?;
+            info!(path = %path.display(), "Packaged archive as tar.gz");
+        }
+    }
+
+    Ok(())
+}
+
+/// Derive the package path: a sibling of `output_dir` named after it, with
+/// `ext` appended (e.g. `out/` → `out.zip`).
+fn package_path(output_dir: &std::path::Path, ext: &str) -> std::path::PathBuf {
+    let name = output_dir
+        .file_name()
+        .map_or_else(|| "archive".to_string(), |n| n.to_string_lossy().to_string());
+    output_dir
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."))
+        .join(format!("{name}.{ext}"))
+}
+
+/// Recursively collect every regular file under `dir`, relative to `dir`.
+fn collect_package_entries(dir: &std::path::Path) -> anyhow::Result<Vec<std::path::PathBuf>> {
+    let mut entries = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+
+    while let Some(current) = stack.pop() {
+        for entry in std::fs::read_dir(&current)
+            .with_context(|| format!("Failed to read directory {}", current.display()))?
+        {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                entries.push(path.strip_prefix(dir)?.to_path_buf());
+            }
+        }
+    }
+
+    entries.sort();
+    Ok(entries)
+}
+
+/// Build a standard zip archive at `dest` containing every entry in
+/// `entries`, read from `base_dir`.
+fn write_zip(
+    dest: &std::path::Path,
+    base_dir: &std::path::Path,
+    entries: &[std::path::PathBuf],
+) -> anyhow::Result<()> {
+    let file = std::fs::File::create(dest)
+        .with_context(|| format!("Failed to create {}", dest.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for relative in entries {
+        let contents = std::fs::read(base_dir.join(relative))?;
+        zip.start_file(relative.to_string_lossy(), options)?;
+        zip.write_all(&contents)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+/// Build a gzip-compressed tarball at `dest`, streaming the tar bytes
+/// through `async-compression`'s gzip encoder.
+async fn write_tar_gz(
+    dest: &std::path::Path,
+    base_dir: &std::path::Path,
+    entries: &[std::path::PathBuf],
+) -> anyhow::Result<()> {
+    use async_compression::tokio::write::GzipEncoder;
+    use tokio::io::AsyncWriteExt;
+
+    // `tar::Builder` only writes synchronously, so the (uncompressed) tar
+    // stream is built in memory first, then piped through the async gzip
+    // encoder — the archives here are a single Substack's worth of posts,
+    // not large enough to warrant a fully streaming tar writer.
+    let mut tar_bytes = Vec::new();
+    {
+        let mut builder = tar::Builder::new(&mut tar_bytes);
+        for relative in entries {
+            builder.append_path_with_name(base_dir.join(relative), relative)?;
+        }
+        builder.finish()?;
+    }
+
+    let file = tokio::fs::File::create(dest)
+        .await
+        .with_context(|| format!("Failed to create {}", dest.display()))?;
+    let mut encoder = GzipEncoder::new(file);
+    encoder.write_all(&tar_bytes).await?;
+    encoder.shutdown().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_post_date_accepts_rfc3339_and_rejects_garbage() {
+        assert!(parse_post_date("2024-01-02T15:00:00+00:00").is_some());
+        assert!(parse_post_date("not-a-date").is_none());
+    }
+
+    #[test]
+    fn xml_escape_escapes_reserved_characters() {
+        assert_eq!(
+            xml_escape(r#"<Tom & "Jerry">"#),
+            "&lt;Tom &amp; &quot;Jerry&quot;&gt;"
+        );
+    }
+
+    fn sample_post(id: u64, slug: &str, post_date: &str) -> SubstackPost {
+        SubstackPost {
+            id,
+            title: format!("Post {id}"),
+            slug: slug.to_string(),
+            post_date: post_date.to_string(),
+            canonical_url: format!("https://example.substack.com/p/{slug}"),
+            description: format!("Description for {slug}"),
+            body_html: None,
+            cover_image: None,
+        }
+    }
+
+    fn feed_test_config(output_dir: &std::path::Path) -> AppConfig {
+        use crate::cli::Cli;
+        use clap::Parser;
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+        ])
+        .unwrap();
+        let mut config = if let crate::cli::Commands::Download(ref dl) = cli.command {
+            AppConfig::from_cli(&cli, Some(dl)).unwrap()
+        } else {
+            panic!("expected Download");
+        };
+        config.output_dir = output_dir.to_path_buf();
+        config
+    }
+
+    #[tokio::test]
+    async fn generate_feed_sorts_newest_first_and_links_to_post_files() {
+        let tmp = std::env::temp_dir().join("robustack-dl-archive-test-feed");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+        let config = feed_test_config(&tmp);
+
+        let posts = vec![
+            sample_post(1, "older-post", "2024-01-01T00:00:00+00:00"),
+            sample_post(2, "newer-post", "2024-06-01T00:00:00+00:00"),
+        ];
+        generate_feed(&posts, &config).await.unwrap();
+
+        let xml = std::fs::read_to_string(tmp.join("feed.xml")).unwrap();
+        let newer_pos = xml.find("newer-post.html").unwrap();
+        let older_pos = xml.find("older-post.html").unwrap();
+        assert!(newer_pos < older_pos, "feed should list newest entries first");
+        assert!(xml.contains("<updated>2024-06-01T00:00:00+00:00</updated>"));
+        assert!(xml.contains("<id>urn:robustack-dl:post:2</id>"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn generate_feed_is_noop_for_empty_posts() {
+        let tmp = std::env::temp_dir().join("robustack-dl-archive-test-feed-empty");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+        let config = feed_test_config(&tmp);
+
+        generate_feed(&[], &config).await.unwrap();
+        assert!(!tmp.join("feed.xml").exists());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn generate_feed_compresses_output_and_suffixes_filename() {
+        let tmp = std::env::temp_dir().join("robustack-dl-archive-test-feed-compressed");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+        let mut config = feed_test_config(&tmp);
+        config.compress = Some(crate::cli::CompressionMode::Gzip);
+
+        let posts = vec![sample_post(1, "a-post", "2024-01-01T00:00:00+00:00")];
+        generate_feed(&posts, &config).await.unwrap();
+
+        assert!(!tmp.join("feed.xml").exists());
+        let compressed = std::fs::read(tmp.join("feed.xml.gz")).unwrap();
+        let decoded = crate::compress::decompress_bytes("gzip", &compressed).await.unwrap();
+        assert!(String::from_utf8(decoded).unwrap().contains("a-post.html.gz"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn generate_index_compresses_output_and_suffixes_filename() {
+        let tmp = std::env::temp_dir().join("robustack-dl-archive-test-index-compressed");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+        let mut config = feed_test_config(&tmp);
+        config.compress = Some(crate::cli::CompressionMode::Zstd);
+
+        let posts = vec![sample_post(1, "a-post", "2024-01-01T00:00:00+00:00")];
+        generate_index(&posts, &config).await.unwrap();
+
+        assert!(!tmp.join("index.html").exists());
+        let compressed = std::fs::read(tmp.join("index.html.zst")).unwrap();
+        let decoded = crate::compress::decompress_bytes("zstd", &compressed).await.unwrap();
+        assert!(String::from_utf8(decoded).unwrap().contains("a-post.html.zst"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn package_path_appends_extension_as_sibling() {
+        let dir = std::path::Path::new("/tmp/robustack-out");
+        assert_eq!(
+            package_path(dir, "zip"),
+            std::path::PathBuf::from("/tmp/robustack-out.zip")
+        );
+        assert_eq!(
+            package_path(dir, "tar.gz"),
+            std::path::PathBuf::from("/tmp/robustack-out.tar.gz")
+        );
+    }
+
+    #[test]
+    fn collect_package_entries_walks_nested_dirs() {
+        let tmp = std::env::temp_dir().join("robustack-dl-archive-test-walk");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(tmp.join("images")).unwrap();
+        std::fs::write(tmp.join("index.html"), "x").unwrap();
+        std::fs::write(tmp.join("manifest.json"), "{}").unwrap();
+        std::fs::write(tmp.join("images/a.png"), "y").unwrap();
+
+        let mut entries = collect_package_entries(&tmp).unwrap();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                std::path::PathBuf::from("images/a.png"),
+                std::path::PathBuf::from("index.html"),
+                std::path::PathBuf::from("manifest.json"),
+            ]
+        );
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn package_is_noop_for_dir_format_and_dry_run() {
+        let tmp = std::env::temp_dir().join("robustack-dl-archive-test-noop");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+
+        use crate::cli::Cli;
+        use clap::Parser;
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.com",
+            "--dry-run",
+        ])
+        .unwrap();
+        let mut config = if let crate::cli::Commands::Download(ref dl) = cli.command {
+            AppConfig::from_cli(&cli, Some(dl)).unwrap()
+        } else {
+            panic!("expected Download");
+        };
+        config.output_dir = tmp.clone();
+        config.archive_format = ArchiveFormat::Zip;
+
+        assert!(package(&config).await.is_ok());
+        assert!(!tmp.with_extension("zip").exists());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
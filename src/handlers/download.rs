@@ -18,20 +18,113 @@
 //! 2. Fetch post listings from the Substack API.
 //! 3. For each post: check manifest → fetch content → hash → write (if new).
 //! 4. Optionally download images and file attachments.
-//! 5. Optionally generate an archive index page.
+//! 5. Optionally generate an archive index page and Atom feed.
 //! 6. Persist the updated manifest.
+//! 7. Optionally package the output directory as zip/tar.gz.
 //!
 //! # Idempotency
 //! Every write is guarded by `integrity::should_skip()`. Re-running
 //! the same download command produces zero new I/O if nothing changed.
+//!
+//! # Local cache
+//! Asset fetches (images/attachments) also consult `cache::lookup()`,
+//! keyed by the source URL rather than content hash, so an interrupted
+//! run can resume without re-downloading assets. See [`crate::cache`].
+//!
+//! # Incremental re-sync
+//! `config.http_cache_dir` defaults to `<output>/.robustack-cache` (unless
+//! `--http-cache-dir` overrides it or `--no-cache` disables it), so the
+//! listing fetch and any canonical-URL content fallback — both made
+//! through `client.get_text`/`get_bytes` — send `If-None-Match`/
+//! `If-Modified-Since` on the next run and reuse the cached body on a
+//! `304` instead of re-fetching. See [`crate::http_cache`].
+//!
+//! # File extensions
+//! Assets are named `<hash>.<ext>`, where `<ext>` is resolved by
+//! [`crate::sniff::sniff_extension`] from the server `Content-Type` (when
+//! specific) or the body's magic number — not the source URL, which is
+//! frequently extensionless.
+//!
+//! # EPUB output
+//! `OutputFormat::Epub` skips the per-post file write entirely — chapters
+//! are accumulated as the post loop runs and handed to
+//! [`crate::handlers::epub::build`] afterward as one `archive.epub`. See
+//! [`crate::handlers::epub`].
+//!
+//! # Asset rewriting
+//! `process_images`/`process_attachments` walk the post body with
+//! [`lol_html`] rather than matching tags with a regex, so a URL that
+//! happens to appear in unrelated text or an unrelated attribute is never
+//! clobbered. Collection and rewriting are two separate passes: the first
+//! walk collects `img[src]`, `img[srcset]`, `source[srcset]`, and
+//! `a[href]` values, those URLs are deduped and fetched up to
+//! `config.max_concurrent` at once via [`fetch_concurrently`], and a
+//! second walk sets the matching attribute to the resolved local path
+//! wherever a fetch succeeded — leaving anything that failed pointing at
+//! its original URL.
+//!
+//! # Output compression
+//! When `config.compress` is set, post bodies and every asset written by
+//! `download_asset`/`download_attachment` are piped through
+//! [`crate::compress::compress_bytes`] immediately before the final write,
+//! and the filename gains the matching suffix (`.gz`/`.br`/`.zst`). The digest
+//! stored in the manifest is always computed over the *uncompressed*
+//! bytes first, so re-running with `--compress` toggled never invalidates
+//! `integrity::should_skip`'s idempotency check — only the bytes on disk
+//! and the recorded `compressed_size`/`encoding` change.
+//!
+//! # Subresource integrity
+//! `download_asset`/`download_attachment` additionally record
+//! [`integrity::compute_sri`]'s output on `ManifestEntry::sri`, alongside
+//! the primary `digest`/`algo` pair used for idempotency. `audit --verify`
+//! checks it separately, so a tampered file that happens to collide on the
+//! primary algorithm is still caught.
+//!
+//! # Readability extraction
+//! When `config.readability` is set, `"md"`/`"txt"` output is passed
+//! through [`crate::processor::extract_article`] before conversion, so
+//! the saved file is just the post body rather than surrounding nav/
+//! subscribe/share/comment chrome. `"html"`/`"epub"` output always keeps
+//! the raw document.
+//!
+//! # Domain gating
+//! `config.domain_allow`/`config.domain_deny` are comma-separated host
+//! suffixes applied to every embedded-resource URL before it's queued for
+//! fetch — images, attachments, and (in `OutputFormat::Single` mode)
+//! inlined stylesheets/scripts/CSS `url(...)` references alike. A deny
+//! match always wins; otherwise a non-empty allowlist restricts fetches to
+//! matching hosts. A rejected URL is treated exactly like a failed fetch:
+//! left unresolved and pointing at its original address. See
+//! [`is_allowed_domain`].
+//!
+//! # Single-file HTML output
+//! `OutputFormat::Single` produces one portable `.html` file per post with
+//! every `<img>`, `<link rel="stylesheet">`, `<script src>`, and absolute
+//! CSS `url(...)` reference inside a fetched stylesheet replaced by an
+//! embedded `data:` URI (see
+//! [`inline_resources`]), instead of `process_images`/`process_attachments`'
+//! usual rewrite-to-a-local-path. Nothing is written to `images_dir`/
+//! `files_dir` and no asset manifest entries are recorded — only the post's
+//! own `.html` file is — so `--download-images`/`--download-files` have no
+//! effect in this mode.
+//!
+//! # Signed manifests
+//! When `config.sign_key`/`--sign-key` is set, the manifest is persisted
+//! with [`Manifest::save_signed`] instead of [`Manifest::save`], so
+//! `audit --verify-key` can later confirm the manifest itself wasn't
+//! edited after the fact, not merely that files match whatever manifest
+//! happens to be on disk.
 
+use base64::Engine as _;
+use futures_util::StreamExt;
 use tracing::{info, warn};
 
 use crate::client::HttpClient;
-use crate::config::{AppConfig, OutputFormat};
+use crate::config::{AppConfig, HashAlgo, OutputFormat};
 use crate::integrity::{self, Manifest};
 use anyhow::Context;
-use regex::Regex;
+use lol_html::{element, HtmlRewriter, Settings};
+use std::collections::HashMap;
 use std::io::Write;
 
 /// Execute the download pipeline.
@@ -56,7 +149,7 @@ pub async fn run(url: &str, config: &AppConfig, client: &dyn HttpClient) -> anyh
     let mut manifest = if config.dry_run {
         Manifest::default()
     } else {
-        Manifest::load_or_create(&config.output_dir)?
+        Manifest::load_or_create(&config.output_dir, &config.sandbox)?
     };
 
     info!(
@@ -75,6 +168,12 @@ pub async fn run(url: &str, config: &AppConfig, client: &dyn HttpClient) -> anyh
     let posts = crate::handlers::substack::fetch_posts(url, config, client).await?;
     info!(count = posts.len(), "Found posts");
 
+    // EPUB bundles every post into one `archive.epub` instead of writing a
+    // file per post, so chapters are accumulated here and handed to
+    // `handlers::epub::build` once the loop below has finished rewriting
+    // each post's images/attachments.
+    let mut epub_chapters = Vec::new();
+
     for post in &posts {
         let span = tracing::info_span!("post", slug = %post.slug);
         let _enter = span.enter();
@@ -90,21 +189,56 @@ pub async fn run(url: &str, config: &AppConfig, client: &dyn HttpClient) -> anyh
         // Prepare working content (mutable for rewriting).
         let mut final_html = raw_html.clone();
 
-        // Step 4: Download images if enabled.
-        if config.download_images {
-            final_html = process_images(&final_html, config, client, &mut manifest).await;
+        // Step 4/5: Download images/attachments if enabled — except in
+        // "single" mode, where every resource is inlined as a `data:` URI
+        // instead (implying both flags and skipping images_dir/files_dir
+        // entirely).
+        if config.format == OutputFormat::Single {
+            final_html = inline_resources(&final_html, config, client).await;
+        } else {
+            if config.download_images {
+                final_html = process_images(&final_html, config, client, &mut manifest).await;
+            }
+            if config.download_files {
+                final_html = process_attachments(&final_html, config, client, &mut manifest).await;
+            }
         }
 
-        // Step 5: Download attachments if enabled.
-        if config.download_files {
-            final_html = process_attachments(&final_html, config, client, &mut manifest).await;
+        // EPUB has no per-post file — the whole post list becomes one
+        // `archive.epub` after this loop (see below).
+        if config.format == OutputFormat::Epub {
+            epub_chapters.push((
+                post.post_date.clone(),
+                crate::handlers::epub::Chapter {
+                    title: post.title.clone(),
+                    slug: post.slug.clone(),
+                    source_url: post.canonical_url.clone(),
+                    body_html: final_html,
+                },
+            ));
+            continue;
         }
 
         // Step 6: Transform to target format.
         let output_content = match config.format {
-            OutputFormat::Html => final_html,
-            OutputFormat::Md => crate::processor::html_to_markdown(&final_html),
-            OutputFormat::Txt => crate::processor::html_to_text(&final_html),
+            OutputFormat::Html | OutputFormat::Single => final_html,
+            OutputFormat::Md => {
+                let body = if config.readability {
+                    crate::processor::extract_article(&final_html)
+                } else {
+                    final_html
+                };
+                crate::processor::html_to_markdown(&body)
+            }
+            OutputFormat::Txt => {
+                let body = if config.readability {
+                    crate::processor::extract_article(&final_html)
+                } else {
+                    final_html
+                };
+                crate::processor::html_to_text(&body)
+            }
+            OutputFormat::Epub => unreachable!("handled above"),
         };
 
         let output_content = if config.add_source_url {
@@ -114,104 +248,417 @@ pub async fn run(url: &str, config: &AppConfig, client: &dyn HttpClient) -> anyh
         };
 
         // Calculate hash of what we are about to save.
-        let hash = integrity::sha256_hex(output_content.as_bytes());
+        let hash = integrity::digest_hex(config.hash_algo, output_content.as_bytes());
 
         // Determine filename.
         let ext = match config.format {
-            OutputFormat::Html => "html",
+            OutputFormat::Html | OutputFormat::Single => "html",
             OutputFormat::Md => "md",
             OutputFormat::Txt => "txt",
+            OutputFormat::Epub => unreachable!("handled above"),
         };
         let safe_slug = integrity::sanitize_filename(&post.slug);
-        let filename = format!("{safe_slug}.{ext}");
+        let filename = match config.compress {
+            Some(mode) => format!("{safe_slug}.{ext}{}", crate::compress::suffix(mode)),
+            None => format!("{safe_slug}.{ext}"),
+        };
 
         // Check idempotency.
-        if integrity::should_skip(&manifest, &hash, &config.output_dir, &filename) {
+        if integrity::should_skip(&manifest, &hash, &config.output_dir, &filename, &config.sandbox) {
             info!("Skipping (up to date)");
             continue;
         }
 
-        // Save file.
+        // Save file. The digest above was taken over the uncompressed
+        // `output_content`, so idempotency is unaffected by `--compress`.
         if !config.dry_run {
             let path = config.output_dir.join(&filename);
+            let (write_bytes, compressed_size, encoding) = match config.compress {
+                Some(mode) => {
+                    let compressed =
+                        crate::compress::compress_bytes(mode, output_content.as_bytes()).await?;
+                    let compressed_len = compressed.len() as u64;
+                    (compressed, Some(compressed_len), Some(crate::compress::encoding_label(mode).to_owned()))
+                }
+                None => (output_content.as_bytes().to_vec(), None, None),
+            };
             let mut file = std::fs::File::create(&path).context("Failed to create output file")?;
-            file.write_all(output_content.as_bytes())?;
+            file.write_all(&write_bytes)?;
             info!(path = %path.display(), "Saved post");
 
             // Update manifest.
             manifest.insert(integrity::ManifestEntry {
                 local_path: filename,
-                sha256: hash,
+                digest: hash,
                 source_url: post.canonical_url.clone(),
                 size: output_content.len() as u64,
                 downloaded_at: chrono::Utc::now().to_rfc3339(),
+                slug: Some(post.slug.clone()),
+                post_date: Some(post.post_date.clone()),
+                algo: config.hash_algo,
+                compressed_size,
+                encoding,
+                sri: None,
             });
         }
     }
 
-    // Step 8: Create archive index.
+    // Step 7b: Package EPUB chapters (if that's the target format), using
+    // the first post with a cover image as the EPUB's cover.
+    if config.format == OutputFormat::Epub {
+        let cover_path = if let Some(cover_url) = posts.iter().find_map(|p| p.cover_image.clone()) {
+            match download_asset(&cover_url, &config.images_dir, config, client, &manifest).await {
+                Ok((local_path, entry)) => {
+                    if let Some(entry) = entry {
+                        manifest.insert(entry);
+                    }
+                    Some(local_path)
+                }
+                Err(e) => {
+                    warn!(url = %cover_url, error = %e, "Failed to download cover image");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Spine order is oldest post first, by `post_date`.
+        epub_chapters.sort_by(|a, b| a.0.cmp(&b.0));
+        let epub_chapters: Vec<_> = epub_chapters.into_iter().map(|(_, chapter)| chapter).collect();
+
+        crate::handlers::epub::build(
+            &epub_chapters,
+            cover_path.as_deref(),
+            url,
+            config,
+            &mut manifest,
+        )?;
+    }
+
+    // Step 8: Create archive index and Atom feed.
     if config.create_archive {
-        crate::handlers::archive::generate_index(&posts, config)?;
+        crate::handlers::archive::generate_index(&posts, config).await?;
+        crate::handlers::archive::generate_feed(&posts, config).await?;
     }
 
     // Step 9: Persist manifest.
     if !config.dry_run {
-        manifest.save(&config.output_dir)?;
+        if let Some(sign_key_path) = &config.sign_key {
+            let signing_key = integrity::load_signing_key(sign_key_path)
+                .context("Failed to load --sign-key")?;
+            manifest.save_signed(&config.output_dir, &signing_key, &config.sandbox)?;
+        } else {
+            manifest.save(&config.output_dir, &config.sandbox)?;
+        }
     }
 
+    // Step 10: Package the output directory, if requested.
+    crate::handlers::archive::package(config).await?;
+
     info!("Download completed");
     Ok(())
 }
 
-/// Helper to download an asset (image/file) and return relative path.
+/// Helper to download an asset (image). Returns its relative path plus the
+/// manifest entry to record, or `None` if `should_skip` found it already
+/// up to date.
+///
+/// Images and file attachments are fetched, hashed, and written
+/// identically, so this just delegates to [`download_attachment`] — only
+/// the `subdir` convention (`images_dir` vs `files_dir`) differs at the
+/// call site.
 async fn download_asset(
     url: &str,
     subdir: &str,
     config: &AppConfig,
     client: &dyn HttpClient,
-    manifest: &mut Manifest,
-) -> anyhow::Result<String> {
+    manifest: &Manifest,
+) -> anyhow::Result<(String, Option<integrity::ManifestEntry>)> {
+    download_attachment(url, subdir, config, client, manifest).await
+}
+
+/// Helper to download an asset (image or file attachment) and return its
+/// relative path plus the manifest entry to record, or `None` if
+/// `should_skip` found it already up to date.
+///
+/// Routes through [`HttpClient::get_to_file`] so the body streams straight
+/// to disk — resumed and retried on a flaky connection or a declared-
+/// length mismatch — hashed in the same pass, instead of being buffered
+/// whole in memory or re-read afterward to compute its digest. The
+/// content-addressed name isn't known until the hash is available, so the
+/// body lands under a scratch name first and is renamed once hashed.
+/// Whether a scratch file surviving a killed process is trusted and
+/// resumed on the next run, rather than discarded, depends on
+/// `config.resume`/`--resume`.
+///
+/// Takes `manifest` by shared reference rather than mutating it directly:
+/// callers run many of these concurrently via [`fetch_concurrently`] and
+/// apply the returned entries single-threaded once every task completes.
+async fn download_attachment(
+    url: &str,
+    subdir: &str,
+    config: &AppConfig,
+    client: &dyn HttpClient,
+    manifest: &Manifest,
+) -> anyhow::Result<(String, Option<integrity::ManifestEntry>)> {
+    std::fs::create_dir_all(config.output_dir.join(subdir))?;
+
+    let scratch_name = format!(".{}.part", integrity::sha256_hex(url.as_bytes()));
+    let scratch_path = config.output_dir.join(subdir).join(&scratch_name);
+    let cache_dir = config.output_dir.join(&config.cache_dir);
+
+    // Consult the URL-keyed cache before touching the network. On a cache
+    // miss, `get_to_file` hashes the body as it streams to disk, so that
+    // digest is reused directly when the configured algorithm is SHA-256
+    // rather than re-reading the file. A cache hit (plain copy) and a
+    // non-SHA-256 algorithm still need a dedicated hashing pass below.
+    let (size, precomputed_sha256, content_type) = if config.cache_enabled {
+        if let Some(cached) = crate::cache::lookup(&cache_dir, url) {
+            let written = crate::cache::materialize(&cached, &scratch_path)?;
+            (written, None, None)
+        } else {
+            let (written, sha256, content_type) = client.get_to_file(url, &scratch_path).await?;
+            std::fs::create_dir_all(&cache_dir)?;
+            std::fs::copy(&scratch_path, crate::cache::entry_path(&cache_dir, url))?;
+            (written, Some(sha256), content_type)
+        }
+    } else {
+        let (written, sha256, content_type) = client.get_to_file(url, &scratch_path).await?;
+        (written, Some(sha256), content_type)
+    };
+
     if config.dry_run {
-        return Ok(format!("{subdir}/dry-run-asset"));
+        let _ = std::fs::remove_file(&scratch_path);
+        return Ok((format!("{subdir}/dry-run-asset"), None));
     }
 
-    // Attempt download
-    let bytes = client.get_bytes(url).await?;
-    let hash = integrity::sha256_hex(&bytes);
-
-    // Derive extension
-    let ext = std::path::Path::new(url)
-        .extension()
-        .and_then(|s| s.to_str())
-        .unwrap_or("bin");
+    let hash = match (config.hash_algo, precomputed_sha256) {
+        (HashAlgo::Sha256, Some(sha256)) => sha256,
+        _ => integrity::digest_file(config.hash_algo, &scratch_path)?,
+    };
 
-    let filename = format!("{hash}.{ext}");
+    // Prefer a specific server-reported Content-Type, falling back to
+    // sniffing the downloaded file's magic number (a cache hit has no
+    // Content-Type to go on either).
+    let sniff_window = read_sniff_window(&scratch_path);
+    let ext = crate::sniff::sniff_extension(content_type.as_deref(), &sniff_window);
+    let filename = match config.compress {
+        Some(mode) => format!("{hash}.{ext}{}", crate::compress::suffix(mode)),
+        None => format!("{hash}.{ext}"),
+    };
     let sub_path = std::path::Path::new(subdir).join(&filename);
     let full_path = config.output_dir.join(&sub_path);
 
-    // Ensure subdir exists
-    std::fs::create_dir_all(config.output_dir.join(subdir))?;
-
     if integrity::should_skip(
         manifest,
         &hash,
         &config.output_dir,
         sub_path.to_str().unwrap(),
+        &config.sandbox,
     ) {
-        return Ok(sub_path.to_string_lossy().to_string());
+        let _ = std::fs::remove_file(&scratch_path);
+        return Ok((sub_path.to_string_lossy().to_string(), None));
     }
 
-    let mut file = std::fs::File::create(&full_path)?;
-    file.write_all(&bytes)?;
-    manifest.insert(integrity::ManifestEntry {
+    // Computed from the scratch file before it's compressed/renamed/removed
+    // below, so `sri` always describes the same uncompressed content as
+    // `hash`/`size`.
+    let sri = std::fs::read(&scratch_path).ok().map(|raw| integrity::compute_sri(&raw));
+
+    // `hash`/`size` always describe the uncompressed scratch file, so the
+    // idempotency check above is unaffected by `--compress`.
+    let (compressed_size, encoding) = match config.compress {
+        Some(compress_mode) => {
+            let raw = std::fs::read(&scratch_path)?;
+            let compressed = crate::compress::compress_bytes(compress_mode, &raw).await?;
+            let compressed_len = compressed.len() as u64;
+            std::fs::write(&full_path, &compressed)?;
+            let _ = std::fs::remove_file(&scratch_path);
+            (Some(compressed_len), Some(crate::compress::encoding_label(compress_mode).to_owned()))
+        }
+        None => {
+            std::fs::rename(&scratch_path, &full_path)?;
+            (None, None)
+        }
+    };
+    let entry = integrity::ManifestEntry {
         source_url: url.to_string(),
-        sha256: hash,
+        digest: hash,
         local_path: sub_path.to_string_lossy().to_string(),
-        size: bytes.len() as u64,
+        size,
         downloaded_at: chrono::Utc::now().to_rfc3339(),
+        slug: None,
+        post_date: None,
+        algo: config.hash_algo,
+        compressed_size,
+        encoding,
+        sri,
+    };
+
+    Ok((sub_path.to_string_lossy().to_string(), Some(entry)))
+}
+
+/// Run one `fetch` per URL in `urls` with at most `config.max_concurrent`
+/// in flight at a time, returning `(url, result)` pairs in completion
+/// order.
+///
+/// There's no separate token bucket here: every request still goes through
+/// `client`, which already rate-limits and bounds its own in-flight count
+/// (see [`crate::client::ReqwestClient`]) — this only adds the fan-out, so
+/// the asset passes below don't `await` one asset at a time.
+async fn fetch_concurrently<F, Fut, T>(
+    urls: Vec<String>,
+    max_concurrent: u32,
+    fetch: F,
+) -> Vec<(String, anyhow::Result<T>)>
+where
+    F: Fn(String) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<T>>,
+{
+    futures_util::stream::iter(urls)
+        .map(|url| {
+            let fut = fetch(url.clone());
+            async move { (url, fut.await) }
+        })
+        .buffer_unordered(max_concurrent.max(1) as usize)
+        .collect()
+        .await
+}
+
+/// Deduplicate `urls`, preserving first-seen order.
+fn dedup_preserve_order(urls: Vec<String>) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    urls.into_iter().filter(|url| seen.insert(url.clone())).collect()
+}
+
+/// Collect every attribute value matching `selector`/`attr` in document
+/// order, without mutating `html`. `selector` must be a single simple CSS
+/// selector lol_html understands (e.g. `"img[src]"`).
+fn collect_attr(html: &str, selector: &str, attr: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let attr = attr.to_string();
+    let handler = element!(selector, |el| {
+        if let Some(v) = el.get_attribute(&attr) {
+            values.push(v);
+        }
+        Ok(())
     });
+    let mut rewriter = HtmlRewriter::new(
+        Settings {
+            element_content_handlers: vec![handler],
+            ..Settings::default()
+        },
+        |_: &[u8]| {},
+    );
+    let _ = rewriter.write(html.as_bytes()).and_then(|()| rewriter.end());
+    values
+}
 
-    Ok(sub_path.to_string_lossy().to_string())
+/// Parse a `srcset` attribute value into `(url, descriptor)` pairs, where
+/// `descriptor` is the trailing width/density hint (`"480w"`, `"2x"`) or
+/// an empty string when the candidate has none.
+fn parse_srcset(value: &str) -> Vec<(String, String)> {
+    value
+        .split(',')
+        .filter_map(|candidate| {
+            let candidate = candidate.trim();
+            if candidate.is_empty() {
+                return None;
+            }
+            let mut parts = candidate.splitn(2, char::is_whitespace);
+            let url = parts.next()?.to_string();
+            let descriptor = parts.next().unwrap_or("").trim().to_string();
+            Some((url, descriptor))
+        })
+        .collect()
+}
+
+/// Rebuild a `srcset` attribute value, substituting any URL found in
+/// `resolved` with its mapped local path and leaving unresolved URLs as-is.
+fn rewrite_srcset(value: &str, resolved: &HashMap<String, String>) -> String {
+    parse_srcset(value)
+        .into_iter()
+        .map(|(url, descriptor)| {
+            let url = resolved.get(&url).cloned().unwrap_or(url);
+            if descriptor.is_empty() {
+                url
+            } else {
+                format!("{url} {descriptor}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Rewrite every `img[src]`, `img[srcset]`, and `source[srcset]` attribute
+/// in `html`, substituting resolved URLs and re-serializing. Falls back to
+/// the original `html` unchanged if the rewrite pass itself fails.
+fn rewrite_image_attrs(html: &str, resolved: &HashMap<String, String>) -> String {
+    let mut output = Vec::new();
+    let result = {
+        let mut rewriter = HtmlRewriter::new(
+            Settings {
+                element_content_handlers: vec![
+                    element!("img[src]", |el| {
+                        if let Some(src) = el.get_attribute("src") {
+                            if let Some(local) = resolved.get(&src) {
+                                let _ = el.set_attribute("src", local);
+                            }
+                        }
+                        Ok(())
+                    }),
+                    element!("img[srcset]", |el| {
+                        if let Some(srcset) = el.get_attribute("srcset") {
+                            let _ = el.set_attribute("srcset", &rewrite_srcset(&srcset, resolved));
+                        }
+                        Ok(())
+                    }),
+                    element!("source[srcset]", |el| {
+                        if let Some(srcset) = el.get_attribute("srcset") {
+                            let _ = el.set_attribute("srcset", &rewrite_srcset(&srcset, resolved));
+                        }
+                        Ok(())
+                    }),
+                ],
+                ..Settings::default()
+            },
+            |c: &[u8]| output.extend_from_slice(c),
+        );
+        rewriter.write(html.as_bytes()).and_then(|()| rewriter.end())
+    };
+    match result {
+        Ok(()) => String::from_utf8(output).unwrap_or_else(|_| html.to_string()),
+        Err(_) => html.to_string(),
+    }
+}
+
+/// Rewrite every `a[href]` attribute in `html` found in `resolved`,
+/// re-serializing. Falls back to the original `html` unchanged if the
+/// rewrite pass itself fails.
+fn rewrite_link_attrs(html: &str, resolved: &HashMap<String, String>) -> String {
+    let mut output = Vec::new();
+    let result = {
+        let mut rewriter = HtmlRewriter::new(
+            Settings {
+                element_content_handlers: vec![element!("a[href]", |el| {
+                    if let Some(href) = el.get_attribute("href") {
+                        if let Some(local) = resolved.get(&href) {
+                            let _ = el.set_attribute("href", local);
+                        }
+                    }
+                    Ok(())
+                })],
+                ..Settings::default()
+            },
+            |c: &[u8]| output.extend_from_slice(c),
+        );
+        rewriter.write(html.as_bytes()).and_then(|()| rewriter.end())
+    };
+    match result {
+        Ok(()) => String::from_utf8(output).unwrap_or_else(|_| html.to_string()),
+        Err(_) => html.to_string(),
+    }
 }
 
 async fn process_images(
@@ -220,21 +667,45 @@ async fn process_images(
     client: &dyn HttpClient,
     manifest: &mut Manifest,
 ) -> String {
-    let img_regex = Regex::new(r#"<img[^>]+src="([^"]+)"[^>]*>"#).expect("invalid regex");
-    let mut final_html = html.to_string();
-
-    for cap in img_regex.captures_iter(html) {
-        if let Some(src_match) = cap.get(1) {
-            let src_url = src_match.as_str();
-            match download_asset(src_url, &config.images_dir, config, client, manifest).await {
-                Ok(local_path) => {
-                    final_html = final_html.replace(src_url, &local_path);
+    let mut urls = collect_attr(html, "img[src]", "src");
+    for srcset in collect_attr(html, "img[srcset]", "srcset") {
+        urls.extend(parse_srcset(&srcset).into_iter().map(|(url, _)| url));
+    }
+    for srcset in collect_attr(html, "source[srcset]", "srcset") {
+        urls.extend(parse_srcset(&srcset).into_iter().map(|(url, _)| url));
+    }
+    let urls: Vec<String> = dedup_preserve_order(urls)
+        .into_iter()
+        .filter(|url| is_allowed_domain(url, &config.domain_allow, &config.domain_deny))
+        .collect();
+    if urls.is_empty() {
+        return html.to_string();
+    }
+
+    let results = {
+        let manifest_ref: &Manifest = manifest;
+        fetch_concurrently(urls, config.max_concurrent, |url| {
+            download_asset(&url, &config.images_dir, config, client, manifest_ref)
+        })
+        .await
+    };
+
+    let mut resolved = HashMap::new();
+    for (src_url, result) in results {
+        match result {
+            Ok((local_path, entry)) => {
+                if let Some(entry) = entry {
+                    manifest.insert(entry);
                 }
-                Err(e) => warn!(url = %src_url, error = %e, "Failed to download image"),
+                resolved.insert(src_url, local_path);
             }
+            Err(e) => warn!(url = %src_url, error = %e, "Failed to download image"),
         }
     }
-    final_html
+    if resolved.is_empty() {
+        return html.to_string();
+    }
+    rewrite_image_attrs(html, &resolved)
 }
 
 async fn process_attachments(
@@ -243,25 +714,255 @@ async fn process_attachments(
     client: &dyn HttpClient,
     manifest: &mut Manifest,
 ) -> String {
-    let link_regex = Regex::new(r#"<a[^>]+href="([^"]+)"[^>]*>"#).expect("invalid regex");
-    let mut final_html = html.to_string();
-
-    for cap in link_regex.captures_iter(html) {
-        if let Some(href_match) = cap.get(1) {
-            let href_url = href_match.as_str();
-            if is_allowed_extension(href_url, &config.file_extensions) {
-                match download_asset(href_url, &config.files_dir, config, client, manifest).await {
-                    Ok(local_path) => {
-                        final_html = final_html.replace(href_url, &local_path);
-                    }
-                    Err(e) => {
-                        warn!(url = %href_url, error = %e, "Failed to download attachment");
-                    }
+    let urls: Vec<String> = dedup_preserve_order(collect_attr(html, "a[href]", "href"))
+        .into_iter()
+        .filter(|url| is_allowed_extension(url, &config.file_extensions))
+        .filter(|url| is_allowed_domain(url, &config.domain_allow, &config.domain_deny))
+        .collect();
+    if urls.is_empty() {
+        return html.to_string();
+    }
+
+    let results = {
+        let manifest_ref: &Manifest = manifest;
+        fetch_concurrently(urls, config.max_concurrent, |url| {
+            download_attachment(&url, &config.files_dir, config, client, manifest_ref)
+        })
+        .await
+    };
+
+    let mut resolved = HashMap::new();
+    for (href_url, result) in results {
+        match result {
+            Ok((local_path, entry)) => {
+                if let Some(entry) = entry {
+                    manifest.insert(entry);
                 }
+                resolved.insert(href_url, local_path);
             }
+            Err(e) => warn!(url = %href_url, error = %e, "Failed to download attachment"),
         }
     }
-    final_html
+    if resolved.is_empty() {
+        return html.to_string();
+    }
+    rewrite_link_attrs(html, &resolved)
+}
+
+/// Fetch every `<img>`, `<link rel="stylesheet">`, and `<script src>`
+/// reference in `html` and rewrite it to an embedded `data:` URI — the
+/// `OutputFormat::Single` counterpart to `process_images`/
+/// `process_attachments`. Unlike those, nothing is written to
+/// `images_dir`/`files_dir` and no manifest entries are recorded; a
+/// resource that fails to fetch is left pointing at its original URL
+/// rather than failing the whole post. A resource whose host is rejected
+/// by `config.domain_allow`/`config.domain_deny` is treated the same way —
+/// left pointing at its original URL.
+async fn inline_resources(html: &str, config: &AppConfig, client: &dyn HttpClient) -> String {
+    let mut urls = collect_attr(html, "img[src]", "src");
+    urls.extend(collect_attr(html, "link[rel=\"stylesheet\"][href]", "href"));
+    urls.extend(collect_attr(html, "script[src]", "src"));
+    let urls: Vec<String> = dedup_preserve_order(urls)
+        .into_iter()
+        .filter(|url| is_allowed_domain(url, &config.domain_allow, &config.domain_deny))
+        .collect();
+    if urls.is_empty() {
+        return html.to_string();
+    }
+
+    let results =
+        fetch_concurrently(urls, config.max_concurrent, |url| inline_one(url, config, client))
+            .await;
+
+    let mut resolved = HashMap::new();
+    for (url, result) in results {
+        match result {
+            Ok(data_uri) => {
+                resolved.insert(url, data_uri);
+            }
+            Err(e) => warn!(url = %url, error = %e, "Failed to inline resource"),
+        }
+    }
+    if resolved.is_empty() {
+        return html.to_string();
+    }
+    rewrite_inline_attrs(html, &resolved)
+}
+
+/// Fetch `url` and encode it as a `data:` URI, recursively inlining any
+/// `url(...)` reference found inside it first if it turns out to be CSS.
+async fn inline_one(url: String, config: &AppConfig, client: &dyn HttpClient) -> anyhow::Result<String> {
+    let (bytes, content_type) = client.get_bytes_with_type(&url).await?;
+    let mime = resolve_mime(content_type.as_deref(), &bytes);
+    let bytes = if mime == "text/css" {
+        inline_css_urls(&bytes, config, client).await
+    } else {
+        bytes
+    };
+    Ok(to_data_uri(&mime, &bytes))
+}
+
+/// Fetch `url` and encode it as a `data:` URI without recursing into
+/// nested `url(...)` references — used for resources found inside an
+/// already-fetched stylesheet, where a second level of CSS-in-CSS
+/// rewriting isn't worth the complexity.
+async fn inline_leaf(url: String, client: &dyn HttpClient) -> anyhow::Result<String> {
+    let (bytes, content_type) = client.get_bytes_with_type(&url).await?;
+    let mime = resolve_mime(content_type.as_deref(), &bytes);
+    Ok(to_data_uri(&mime, &bytes))
+}
+
+/// Resolve a MIME type for a fetched resource: the server's `Content-Type`
+/// (stripped of any `; charset=...` parameter) when present, falling back
+/// to [`crate::sniff::sniff_extension`] mapped back to a MIME type.
+fn resolve_mime(content_type: Option<&str>, bytes: &[u8]) -> String {
+    content_type
+        .map(|ct| ct.split(';').next().unwrap_or("").trim().to_owned())
+        .filter(|ct| !ct.is_empty())
+        .unwrap_or_else(|| {
+            crate::sniff::mime_for_extension(crate::sniff::sniff_extension(content_type, bytes))
+                .to_owned()
+        })
+}
+
+/// Base64-encode `bytes` as a `data:<mime>;base64,<payload>` URI.
+fn to_data_uri(mime: &str, bytes: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+    format!("data:{mime};base64,{encoded}")
+}
+
+/// Fetch and inline every absolute `http(s)` `url(...)` reference in
+/// `css_bytes` (e.g. `@font-face`/background-image references), returning
+/// the rewritten stylesheet bytes. Relative references are left as-is —
+/// without the stylesheet's own URL there's nothing to resolve them
+/// against. References whose host is rejected by `config.domain_allow`/
+/// `config.domain_deny` are left untouched as well.
+async fn inline_css_urls(css_bytes: &[u8], config: &AppConfig, client: &dyn HttpClient) -> Vec<u8> {
+    let css = String::from_utf8_lossy(css_bytes).into_owned();
+    let urls: Vec<String> = dedup_preserve_order(extract_css_urls(&css))
+        .into_iter()
+        .filter(|url| is_allowed_domain(url, &config.domain_allow, &config.domain_deny))
+        .collect();
+    if urls.is_empty() {
+        return css_bytes.to_vec();
+    }
+
+    let results =
+        fetch_concurrently(urls, config.max_concurrent, |url| inline_leaf(url, client)).await;
+
+    let mut resolved = HashMap::new();
+    for (url, result) in results {
+        match result {
+            Ok(data_uri) => {
+                resolved.insert(url, data_uri);
+            }
+            Err(e) => warn!(url = %url, error = %e, "Failed to inline CSS url() reference"),
+        }
+    }
+    replace_css_urls(&css, &resolved).into_bytes()
+}
+
+/// Extract every distinct absolute `http(s)` URL referenced via
+/// `url(...)` in `css`, skipping `data:` URIs and relative paths.
+fn extract_css_urls(css: &str) -> Vec<String> {
+    let mut urls = Vec::new();
+    let mut rest = css;
+    while let Some(start) = rest.find("url(") {
+        let after = &rest[start + 4..];
+        let Some(end) = after.find(')') else { break };
+        let raw = after[..end].trim().trim_matches(|c| c == '"' || c == '\'');
+        if raw.starts_with("http://") || raw.starts_with("https://") {
+            urls.push(raw.to_string());
+        }
+        rest = &after[end + 1..];
+    }
+    urls
+}
+
+/// Rewrite every `url(...)` reference in `css` found in `resolved`,
+/// leaving unresolved or non-`http(s)` references untouched.
+fn replace_css_urls(css: &str, resolved: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    let mut rest = css;
+    loop {
+        let Some(start) = rest.find("url(") else {
+            out.push_str(rest);
+            break;
+        };
+        let Some(end_rel) = rest[start + 4..].find(')') else {
+            out.push_str(rest);
+            break;
+        };
+        let end = start + 4 + end_rel;
+        out.push_str(&rest[..start]);
+        let raw = rest[start + 4..end].trim().trim_matches(|c| c == '"' || c == '\'');
+        match resolved.get(raw) {
+            Some(data_uri) => out.push_str(&format!("url(\"{data_uri}\")")),
+            None => out.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    out
+}
+
+/// Rewrite every `img[src]`, `link[rel="stylesheet"][href]`, and
+/// `script[src]` attribute in `html` found in `resolved`, re-serializing.
+/// Falls back to the original `html` unchanged if the rewrite pass itself
+/// fails.
+fn rewrite_inline_attrs(html: &str, resolved: &HashMap<String, String>) -> String {
+    let mut output = Vec::new();
+    let result = {
+        let mut rewriter = HtmlRewriter::new(
+            Settings {
+                element_content_handlers: vec![
+                    element!("img[src]", |el| {
+                        if let Some(src) = el.get_attribute("src") {
+                            if let Some(data_uri) = resolved.get(&src) {
+                                let _ = el.set_attribute("src", data_uri);
+                            }
+                        }
+                        Ok(())
+                    }),
+                    element!("link[rel=\"stylesheet\"][href]", |el| {
+                        if let Some(href) = el.get_attribute("href") {
+                            if let Some(data_uri) = resolved.get(&href) {
+                                let _ = el.set_attribute("href", data_uri);
+                            }
+                        }
+                        Ok(())
+                    }),
+                    element!("script[src]", |el| {
+                        if let Some(src) = el.get_attribute("src") {
+                            if let Some(data_uri) = resolved.get(&src) {
+                                let _ = el.set_attribute("src", data_uri);
+                            }
+                        }
+                        Ok(())
+                    }),
+                ],
+                ..Settings::default()
+            },
+            |c: &[u8]| output.extend_from_slice(c),
+        );
+        rewriter.write(html.as_bytes()).and_then(|()| rewriter.end())
+    };
+    match result {
+        Ok(()) => String::from_utf8(output).unwrap_or_else(|_| html.to_string()),
+        Err(_) => html.to_string(),
+    }
+}
+
+/// Read up to the first 512 bytes of `path` for content sniffing, without
+/// loading the whole (potentially large) file into memory.
+fn read_sniff_window(path: &std::path::Path) -> Vec<u8> {
+    use std::io::Read;
+    let Ok(mut file) = std::fs::File::open(path) else {
+        return Vec::new();
+    };
+    let mut buf = vec![0u8; 512];
+    let read = file.read(&mut buf).unwrap_or(0);
+    buf.truncate(read);
+    buf
 }
 
 fn is_allowed_extension(url: &str, allowlist: &str) -> bool {
@@ -277,6 +978,69 @@ fn is_allowed_extension(url: &str, allowlist: &str) -> bool {
         .any(|e| e.trim().eq_ignore_ascii_case(ext))
 }
 
+/// Returns `true` if `host` is exactly `suffix` or ends with `.{suffix}`,
+/// so a configured suffix like `"substackcdn.com"` also matches
+/// `"foo.substackcdn.com"` without matching `"evilsubstackcdn.com"`.
+fn host_matches_suffix(host: &str, suffix: &str) -> bool {
+    host.eq_ignore_ascii_case(suffix)
+        || host
+            .to_ascii_lowercase()
+            .ends_with(&format!(".{}", suffix.to_ascii_lowercase()))
+}
+
+/// Extract the host portion of an absolute `http(s)` `url`, or `None` if it
+/// doesn't start with a recognized scheme (e.g. a relative path, or a
+/// `data:` URI already inlined by an earlier pass).
+fn url_host(url: &str) -> Option<&str> {
+    // Scheme names are case-insensitive per RFC 3986 — `HTTPS://`/`Http://`
+    // fetch exactly like their lowercase forms, so matching only the
+    // lowercase prefix here would let a trivially-cased URL slip past
+    // `domain_deny` unrecognized.
+    let scheme_end = url.find("://")?;
+    let scheme = &url[..scheme_end];
+    if !scheme.eq_ignore_ascii_case("https") && !scheme.eq_ignore_ascii_case("http") {
+        return None;
+    }
+    let rest = &url[scheme_end + 3..];
+    let host_and_port = rest.split('/').next().unwrap_or(rest);
+    let host = host_and_port.rsplit('@').next().unwrap_or(host_and_port);
+    let host = host.split(':').next().unwrap_or(host);
+    if host.is_empty() {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+/// Returns `true` if `url`'s host passes `domain_allow`/`domain_deny`
+/// (comma-separated host suffixes): a host matching any `domain_deny`
+/// suffix is rejected regardless of `domain_allow`; otherwise, when
+/// `domain_allow` is non-empty, only a host matching one of its suffixes
+/// is accepted. Both lists empty (the default) allows every host. A URL
+/// with no parseable host is allowed through — the fetch itself will fail
+/// downstream if it's truly malformed.
+fn is_allowed_domain(url: &str, domain_allow: &str, domain_deny: &str) -> bool {
+    let Some(host) = url_host(url) else { return true };
+
+    if domain_deny
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .any(|suffix| host_matches_suffix(host, suffix))
+    {
+        return false;
+    }
+
+    if domain_allow.trim().is_empty() {
+        return true;
+    }
+    domain_allow
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .any(|suffix| host_matches_suffix(host, suffix))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -297,6 +1061,20 @@ mod tests {
             }
             Ok("<html>mock</html>".to_string())
         }
+        async fn download_to(&self, _url: &str, dest: &std::path::Path) -> anyhow::Result<u64> {
+            let bytes = b"<html>mock</html>";
+            tokio::fs::write(dest, bytes).await?;
+            Ok(bytes.len() as u64)
+        }
+        async fn get_to_file(
+            &self,
+            _url: &str,
+            dest: &std::path::Path,
+        ) -> anyhow::Result<(u64, String, Option<String>)> {
+            let bytes = b"<html>mock</html>";
+            tokio::fs::write(dest, bytes).await?;
+            Ok((bytes.len() as u64, integrity::sha256_hex(bytes), None))
+        }
         fn rate_limit(&self) -> u32 {
             100
         }
@@ -314,7 +1092,7 @@ mod tests {
         ])
         .unwrap();
         if let crate::cli::Commands::Download(ref dl) = cli.command {
-            AppConfig::from_cli(&cli, dl.limit, Some(dl))
+            AppConfig::from_cli(&cli, Some(dl)).expect("valid config")
         } else {
             panic!("expected Download");
         }
@@ -338,4 +1116,529 @@ mod tests {
             .unwrap();
         assert!(text.contains("posts"));
     }
+
+    /// A mock that serves a fixed body/content-type for any URL, used to
+    /// exercise content-sniffing in `download_asset`/`download_attachment`.
+    #[derive(Debug)]
+    struct SniffableClient {
+        body: Vec<u8>,
+        content_type: Option<String>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for SniffableClient {
+        async fn get_bytes(&self, _url: &str) -> anyhow::Result<Vec<u8>> {
+            Ok(self.body.clone())
+        }
+        async fn get_bytes_with_type(&self, _url: &str) -> anyhow::Result<(Vec<u8>, Option<String>)> {
+            Ok((self.body.clone(), self.content_type.clone()))
+        }
+        async fn get_text(&self, _url: &str) -> anyhow::Result<String> {
+            Ok(String::from_utf8_lossy(&self.body).into_owned())
+        }
+        async fn download_to(&self, _url: &str, dest: &std::path::Path) -> anyhow::Result<u64> {
+            tokio::fs::write(dest, &self.body).await?;
+            Ok(self.body.len() as u64)
+        }
+        async fn get_to_file(
+            &self,
+            _url: &str,
+            dest: &std::path::Path,
+        ) -> anyhow::Result<(u64, String, Option<String>)> {
+            tokio::fs::write(dest, &self.body).await?;
+            Ok((
+                self.body.len() as u64,
+                integrity::sha256_hex(&self.body),
+                self.content_type.clone(),
+            ))
+        }
+        fn rate_limit(&self) -> u32 {
+            100
+        }
+    }
+
+    #[tokio::test]
+    async fn download_asset_sniffs_extension_for_extensionless_url() {
+        let mut config = test_config();
+        config.dry_run = false;
+        config.cache_enabled = false;
+        let tmp = std::env::temp_dir().join("robustack_test_download_asset_sniff");
+        let _ = std::fs::remove_dir_all(&tmp);
+        config.output_dir = tmp.clone();
+
+        let client = SniffableClient {
+            body: b"\x89PNG\r\n\x1a\nrest-of-file".to_vec(),
+            content_type: None,
+        };
+        let manifest = Manifest::default();
+
+        let (local_path, entry) = download_asset(
+            "https://cdn.example.com/assets/abc123",
+            "images",
+            &config,
+            &client,
+            &manifest,
+        )
+        .await
+        .unwrap();
+
+        assert!(local_path.ends_with(".png"), "expected .png, got {local_path}");
+        assert!(entry.is_some());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn download_attachment_sniffs_extension_from_content_type() {
+        let mut config = test_config();
+        config.dry_run = false;
+        config.cache_enabled = false;
+        let tmp = std::env::temp_dir().join("robustack_test_download_attachment_sniff");
+        let _ = std::fs::remove_dir_all(&tmp);
+        config.output_dir = tmp.clone();
+
+        let client = SniffableClient {
+            body: b"%PDF-1.4 fake pdf body".to_vec(),
+            content_type: Some("application/pdf".to_string()),
+        };
+        let manifest = Manifest::default();
+
+        let (local_path, entry) = download_attachment(
+            "https://cdn.example.com/files/report",
+            "files",
+            &config,
+            &client,
+            &manifest,
+        )
+        .await
+        .unwrap();
+
+        assert!(local_path.ends_with(".pdf"), "expected .pdf, got {local_path}");
+        assert!(entry.is_some());
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn download_asset_compresses_when_configured_but_hashes_uncompressed() {
+        use crate::config::CompressionMode;
+
+        let mut config = test_config();
+        config.dry_run = false;
+        config.cache_enabled = false;
+        config.compress = Some(CompressionMode::Gzip);
+        let tmp = std::env::temp_dir().join("robustack_test_download_asset_compress");
+        let _ = std::fs::remove_dir_all(&tmp);
+        config.output_dir = tmp.clone();
+
+        let raw_body = b"\x89PNG\r\n\x1a\nrest-of-a-png-file-body".to_vec();
+        let client = SniffableClient {
+            body: raw_body.clone(),
+            content_type: None,
+        };
+        let manifest = Manifest::default();
+
+        let (local_path, entry) = download_asset(
+            "https://cdn.example.com/assets/abc123",
+            "images",
+            &config,
+            &client,
+            &manifest,
+        )
+        .await
+        .unwrap();
+
+        assert!(local_path.ends_with(".png.gz"), "expected .png.gz, got {local_path}");
+        let entry = entry.expect("expected a manifest entry");
+        assert_eq!(entry.digest, integrity::digest_hex(config.hash_algo, &raw_body));
+        assert_eq!(entry.size, raw_body.len() as u64);
+        assert_eq!(entry.encoding.as_deref(), Some("gzip"));
+        let on_disk = std::fs::read(config.output_dir.join(&local_path)).unwrap();
+        assert_eq!(entry.compressed_size, Some(on_disk.len() as u64));
+        assert_ne!(on_disk, raw_body, "file on disk should be gzip-compressed");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn read_sniff_window_reads_leading_bytes_only() {
+        let tmp = std::env::temp_dir().join("robustack_test_read_sniff_window");
+        std::fs::write(&tmp, b"\x89PNGrest-of-a-much-longer-file-body").unwrap();
+        let window = read_sniff_window(&tmp);
+        assert_eq!(&window[..4], b"\x89PNG");
+        let _ = std::fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn read_sniff_window_missing_file_returns_empty() {
+        let window = read_sniff_window(std::path::Path::new(
+            "/nonexistent/robustack-sniff-window-test",
+        ));
+        assert!(window.is_empty());
+    }
+
+    #[test]
+    fn dedup_preserve_order_drops_repeats_keeping_first_seen_order() {
+        let urls = vec!["a.png".to_string(), "b.png".to_string(), "a.png".to_string()];
+        assert_eq!(dedup_preserve_order(urls), vec!["a.png", "b.png"]);
+    }
+
+    #[test]
+    fn collect_attr_finds_img_src_and_ignores_unrelated_urls_in_text() {
+        let html = r#"<p>see https://example.com/not-a-tag.png</p><img src="a.png">"#;
+        assert_eq!(collect_attr(html, "img[src]", "src"), vec!["a.png"]);
+    }
+
+    #[test]
+    fn parse_srcset_splits_url_and_descriptor() {
+        let pairs = parse_srcset("a.png 480w, b.png 800w, c.png");
+        assert_eq!(
+            pairs,
+            vec![
+                ("a.png".to_string(), "480w".to_string()),
+                ("b.png".to_string(), "800w".to_string()),
+                ("c.png".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rewrite_srcset_substitutes_resolved_urls_and_keeps_descriptors() {
+        let mut resolved = HashMap::new();
+        resolved.insert("a.png".to_string(), "images/hash1.png".to_string());
+        let rewritten = rewrite_srcset("a.png 1x, unresolved.png 2x", &resolved);
+        assert_eq!(rewritten, "images/hash1.png 1x, unresolved.png 2x");
+    }
+
+    #[test]
+    fn rewrite_image_attrs_rewrites_src_and_srcset_without_touching_unrelated_text() {
+        let mut resolved = HashMap::new();
+        resolved.insert("a.png".to_string(), "images/hash1.png".to_string());
+        let html = r#"<p>a.png is not a tag</p><img src="a.png" srcset="a.png 1x, b.png 2x">"#;
+        let rewritten = rewrite_image_attrs(html, &resolved);
+        assert!(rewritten.contains(r#"src="images/hash1.png""#));
+        assert!(rewritten.contains(r#"srcset="images/hash1.png 1x, b.png 2x""#));
+        assert!(rewritten.contains("<p>a.png is not a tag</p>"));
+    }
+
+    #[test]
+    fn host_matches_suffix_matches_exact_and_subdomain_not_lookalike() {
+        assert!(host_matches_suffix("substackcdn.com", "substackcdn.com"));
+        assert!(host_matches_suffix("foo.substackcdn.com", "substackcdn.com"));
+        assert!(!host_matches_suffix("evilsubstackcdn.com", "substackcdn.com"));
+        assert!(host_matches_suffix("FOO.SubstackCDN.com", "substackcdn.com"));
+    }
+
+    #[test]
+    fn url_host_extracts_host_ignoring_scheme_port_and_path() {
+        assert_eq!(url_host("https://cdn.example.com/a.png"), Some("cdn.example.com"));
+        assert_eq!(url_host("http://cdn.example.com:8080/a.png"), Some("cdn.example.com"));
+        assert_eq!(url_host("/relative/path.png"), None);
+        assert_eq!(url_host("data:image/png;base64,aa"), None);
+    }
+
+    #[test]
+    fn url_host_is_case_insensitive_to_scheme() {
+        assert_eq!(url_host("HTTPS://tracker.example.com/x.gif"), Some("tracker.example.com"));
+        assert_eq!(url_host("HtTp://tracker.example.com/x.gif"), Some("tracker.example.com"));
+    }
+
+    #[test]
+    fn is_allowed_domain_denies_uppercase_scheme_matching_deny_list() {
+        assert!(!is_allowed_domain(
+            "HTTPS://tracker.example.com/x.gif",
+            "",
+            "tracker.example.com"
+        ));
+    }
+
+    #[test]
+    fn is_allowed_domain_empty_lists_allow_everything() {
+        assert!(is_allowed_domain("https://anything.example.com/a.png", "", ""));
+    }
+
+    #[test]
+    fn is_allowed_domain_allowlist_restricts_to_matching_suffixes() {
+        assert!(is_allowed_domain(
+            "https://foo.substackcdn.com/a.png",
+            "substackcdn.com",
+            ""
+        ));
+        assert!(!is_allowed_domain(
+            "https://evil.example.com/a.png",
+            "substackcdn.com",
+            ""
+        ));
+    }
+
+    #[test]
+    fn is_allowed_domain_denylist_takes_precedence_over_allowlist() {
+        assert!(!is_allowed_domain(
+            "https://evil.substackcdn.com/a.png",
+            "substackcdn.com",
+            "evil.substackcdn.com"
+        ));
+    }
+
+    #[test]
+    fn rewrite_link_attrs_rewrites_href_only() {
+        let mut resolved = HashMap::new();
+        resolved.insert("file.pdf".to_string(), "files/hash1.pdf".to_string());
+        let html = r#"<a href="file.pdf">download file.pdf</a>"#;
+        let rewritten = rewrite_link_attrs(html, &resolved);
+        assert!(rewritten.contains(r#"href="files/hash1.pdf""#));
+        assert!(rewritten.contains(">download file.pdf<"));
+    }
+
+    /// A mock that counts how many distinct URLs it was asked to fetch and
+    /// how many were in flight at once, to exercise `fetch_concurrently`.
+    #[derive(Debug)]
+    struct CountingClient {
+        calls: std::sync::atomic::AtomicUsize,
+        in_flight: std::sync::atomic::AtomicUsize,
+        max_in_flight: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for CountingClient {
+        async fn get_bytes(&self, _url: &str) -> anyhow::Result<Vec<u8>> {
+            Ok(b"\x89PNGrest".to_vec())
+        }
+        async fn get_bytes_with_type(&self, _url: &str) -> anyhow::Result<(Vec<u8>, Option<String>)> {
+            Ok((b"\x89PNGrest".to_vec(), None))
+        }
+        async fn get_text(&self, _url: &str) -> anyhow::Result<String> {
+            Ok(String::new())
+        }
+        async fn download_to(&self, _url: &str, dest: &std::path::Path) -> anyhow::Result<u64> {
+            tokio::fs::write(dest, b"").await?;
+            Ok(0)
+        }
+        async fn get_to_file(
+            &self,
+            _url: &str,
+            dest: &std::path::Path,
+        ) -> anyhow::Result<(u64, String, Option<String>)> {
+            use std::sync::atomic::Ordering;
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            tokio::fs::write(dest, b"").await?;
+            Ok((0, integrity::sha256_hex(b""), None))
+        }
+        fn rate_limit(&self) -> u32 {
+            100
+        }
+    }
+
+    #[tokio::test]
+    async fn process_images_fetches_distinct_urls_concurrently_and_dedupes() {
+        let mut config = test_config();
+        config.dry_run = false;
+        config.cache_enabled = false;
+        config.max_concurrent = 4;
+        let tmp = std::env::temp_dir().join("robustack_test_process_images_concurrent");
+        let _ = std::fs::remove_dir_all(&tmp);
+        config.output_dir = tmp.clone();
+
+        let client = CountingClient {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+            in_flight: std::sync::atomic::AtomicUsize::new(0),
+            max_in_flight: std::sync::atomic::AtomicUsize::new(0),
+        };
+        let mut manifest = Manifest::default();
+
+        let html = r#"
+            <img src="https://cdn.example.com/a.png">
+            <img src="https://cdn.example.com/b.png">
+            <img src="https://cdn.example.com/a.png">
+        "#;
+        let rewritten = process_images(html, &config, &client, &mut manifest).await;
+
+        assert_eq!(client.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert!(client.max_in_flight.load(std::sync::atomic::Ordering::SeqCst) >= 2);
+        assert!(!rewritten.contains("https://cdn.example.com/a.png"));
+        assert!(!rewritten.contains("https://cdn.example.com/b.png"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn process_images_skips_urls_blocked_by_domain_deny() {
+        let mut config = test_config();
+        config.dry_run = false;
+        config.cache_enabled = false;
+        config.domain_deny = "blocked.example.com".to_owned();
+        let tmp = std::env::temp_dir().join("robustack_test_process_images_domain_deny");
+        let _ = std::fs::remove_dir_all(&tmp);
+        config.output_dir = tmp.clone();
+
+        let client = CountingClient {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+            in_flight: std::sync::atomic::AtomicUsize::new(0),
+            max_in_flight: std::sync::atomic::AtomicUsize::new(0),
+        };
+        let mut manifest = Manifest::default();
+
+        let html = r#"
+            <img src="https://cdn.example.com/a.png">
+            <img src="https://blocked.example.com/b.png">
+        "#;
+        let rewritten = process_images(html, &config, &client, &mut manifest).await;
+
+        assert_eq!(client.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert!(!rewritten.contains("https://cdn.example.com/a.png"));
+        assert!(rewritten.contains("https://blocked.example.com/b.png"));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    /// A mock that serves a distinct, fixed `(body, content_type)` per URL,
+    /// used to exercise `inline_resources`'s multi-resource-kind fetching.
+    #[derive(Debug)]
+    struct MultiResponseClient {
+        responses: HashMap<String, (Vec<u8>, Option<String>)>,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for MultiResponseClient {
+        async fn get_bytes(&self, url: &str) -> anyhow::Result<Vec<u8>> {
+            Ok(self.responses.get(url).map(|(b, _)| b.clone()).unwrap_or_default())
+        }
+        async fn get_bytes_with_type(&self, url: &str) -> anyhow::Result<(Vec<u8>, Option<String>)> {
+            self.responses
+                .get(url)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("no mock response for {url}"))
+        }
+        async fn get_text(&self, url: &str) -> anyhow::Result<String> {
+            Ok(String::from_utf8_lossy(&self.get_bytes(url).await?).into_owned())
+        }
+        async fn download_to(&self, url: &str, dest: &std::path::Path) -> anyhow::Result<u64> {
+            let bytes = self.get_bytes(url).await?;
+            tokio::fs::write(dest, &bytes).await?;
+            Ok(bytes.len() as u64)
+        }
+        async fn get_to_file(
+            &self,
+            url: &str,
+            dest: &std::path::Path,
+        ) -> anyhow::Result<(u64, String, Option<String>)> {
+            let bytes = self.get_bytes(url).await?;
+            tokio::fs::write(dest, &bytes).await?;
+            Ok((bytes.len() as u64, integrity::sha256_hex(&bytes), None))
+        }
+        fn rate_limit(&self) -> u32 {
+            100
+        }
+    }
+
+    #[tokio::test]
+    async fn inline_resources_embeds_image_as_data_uri() {
+        let config = test_config();
+        let mut responses = HashMap::new();
+        responses.insert(
+            "https://cdn.example.com/a.png".to_string(),
+            (b"\x89PNGrest".to_vec(), Some("image/png".to_string())),
+        );
+        let client = MultiResponseClient { responses };
+
+        let html = r#"<img src="https://cdn.example.com/a.png">"#;
+        let rewritten = inline_resources(html, &config, &client).await;
+
+        assert!(rewritten.contains("data:image/png;base64,"), "{rewritten}");
+        assert!(!rewritten.contains("https://cdn.example.com/a.png"));
+    }
+
+    #[tokio::test]
+    async fn inline_resources_inlines_nested_css_url_references() {
+        let config = test_config();
+        let mut responses = HashMap::new();
+        responses.insert(
+            "https://cdn.example.com/style.css".to_string(),
+            (
+                b"body { background: url(https://cdn.example.com/bg.png); }".to_vec(),
+                Some("text/css".to_string()),
+            ),
+        );
+        responses.insert(
+            "https://cdn.example.com/bg.png".to_string(),
+            (b"\x89PNGrest".to_vec(), Some("image/png".to_string())),
+        );
+        let client = MultiResponseClient { responses };
+
+        let html = r#"<link rel="stylesheet" href="https://cdn.example.com/style.css">"#;
+        let rewritten = inline_resources(html, &config, &client).await;
+
+        let marker = "data:text/css;base64,";
+        let start = rewritten.find(marker).expect("css should be inlined");
+        let encoded: String = rewritten[start + marker.len()..].chars().take_while(|c| *c != '"').collect();
+        let decoded_css = String::from_utf8(
+            base64::engine::general_purpose::STANDARD.decode(encoded).unwrap(),
+        )
+        .unwrap();
+        assert!(decoded_css.contains("data:image/png;base64,"), "{decoded_css}");
+    }
+
+    #[test]
+    fn extract_css_urls_finds_absolute_and_skips_relative_and_data() {
+        let css = "a { background: url(https://x.com/a.png); } \
+            b { background: url(/relative.png); } \
+            c { background: url(data:image/png;base64,abc); }";
+        assert_eq!(extract_css_urls(css), vec!["https://x.com/a.png"]);
+    }
+
+    #[test]
+    fn replace_css_urls_substitutes_resolved_and_leaves_unresolved() {
+        let mut resolved = HashMap::new();
+        resolved.insert(
+            "https://x.com/a.png".to_string(),
+            "data:image/png;base64,AA==".to_string(),
+        );
+        let css = "a { background: url(https://x.com/a.png); } \
+            b { background: url(/relative.png); }";
+        let rewritten = replace_css_urls(css, &resolved);
+        assert!(rewritten.contains(r#"url("data:image/png;base64,AA==")"#));
+        assert!(rewritten.contains("url(/relative.png)"));
+    }
+
+    #[test]
+    fn rewrite_inline_attrs_rewrites_img_link_and_script() {
+        let mut resolved = HashMap::new();
+        resolved.insert("a.png".to_string(), "data:image/png;base64,AA==".to_string());
+        resolved.insert("style.css".to_string(), "data:text/css;base64,Qg==".to_string());
+        resolved.insert(
+            "app.js".to_string(),
+            "data:application/javascript;base64,Yw==".to_string(),
+        );
+        let html = r#"<link rel="stylesheet" href="style.css"><img src="a.png"><script src="app.js"></script>"#;
+        let rewritten = rewrite_inline_attrs(html, &resolved);
+        assert!(rewritten.contains(r#"src="data:image/png;base64,AA==""#));
+        assert!(rewritten.contains(r#"href="data:text/css;base64,Qg==""#));
+        assert!(rewritten.contains(r#"src="data:application/javascript;base64,Yw==""#));
+    }
+
+    #[test]
+    fn resolve_mime_prefers_content_type_over_sniffing() {
+        assert_eq!(resolve_mime(Some("image/png; charset=binary"), b"whatever"), "image/png");
+    }
+
+    #[test]
+    fn resolve_mime_falls_back_to_sniffing() {
+        assert_eq!(resolve_mime(None, b"\x89PNGrest"), "image/png");
+    }
+
+    #[test]
+    fn to_data_uri_encodes_base64() {
+        let uri = to_data_uri("image/png", b"hi");
+        assert_eq!(
+            uri,
+            format!(
+                "data:image/png;base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(b"hi")
+            )
+        );
+    }
 }
@@ -0,0 +1,458 @@
+//! @project       RoBustack-DL
+//! @organization  CodeBush Collective
+//! @license       GPL-3.0-only
+//! ---------------------------------------------------------------------------
+//! AI PROVENANCE & HUMAN-IN-THE-LOOP (HITL) METADATA:
+//! - Prompt Engineering: Gemini 3 Flash (Strategy, Scoping & Context Tuning)
+//! - Code Generation:   Gemini 3 Pro (Core Systems Engineering & Async Logic)
+//! - Technical Review:  Claude 4.6 Opus (Security Audit & Idiomatic Refinement)
+//! - HITL Verification: Collisio-Adolebitque - AA0614550BDC21F1 (Manual Audit & Final Validation)
+//! ---------------------------------------------------------------------------
+//! Verified Date: 2026-07-30
+//! Integrity: GPG-Signed | HITL-Certified
+//!
+//! EPUB handler — packages a whole Substack as a single EPUB 3 file.
+//!
+//! Unlike the other output formats, EPUB isn't one file per post: every
+//! [`Chapter`] is bundled into one `archive.epub`, so this module is invoked
+//! once after `download::run`'s per-post loop instead of inside it.
+//!
+//! # Structure
+//! - `mimetype` — stored *uncompressed*, the first zip entry (required by
+//!   the EPUB spec for readers that sniff the file type before unzipping).
+//! - `META-INF/container.xml` — points readers at the OPF package document.
+//! - `OEBPS/content.opf` — package metadata, manifest, and spine ordered by
+//!   `post_date`.
+//! - `OEBPS/nav.xhtml` — EPUB 3 navigation document.
+//! - `OEBPS/toc.ncx` — legacy EPUB 2 table of contents, for older readers.
+//! - `OEBPS/{slug}.xhtml` — one chapter per post, from `final_html` after
+//!   image rewriting.
+//! - `OEBPS/images/...` — images already downloaded under `images_dir`,
+//!   copied in verbatim; `<img>` `src` attributes already point here because
+//!   `download::process_images` rewrites them to the same relative path.
+
+use crate::config::AppConfig;
+use crate::integrity::{self, Manifest};
+use anyhow::Context;
+use std::io::Write;
+use tracing::info;
+
+/// One post rendered as an EPUB chapter, in spine order.
+pub struct Chapter {
+    pub title: String,
+    pub slug: String,
+    pub source_url: String,
+    pub body_html: String,
+}
+
+/// Assemble `chapters` (already in spine order) into a single EPUB 3 file
+/// at `{output_dir}/archive.epub`, embedding any images already downloaded
+/// under `config.images_dir`.
+///
+/// `cover_relative_path`, if given, is a path relative to `output_dir`
+/// (e.g. `images/ab12.jpg`) used as the EPUB's cover image.
+///
+/// Like the other handlers, every write is guarded by
+/// `integrity::should_skip()` and recorded in `manifest` for `audit`.
+///
+/// # Errors
+/// Returns `anyhow::Error` if `output_dir` cannot be read or the `.epub`
+/// file cannot be written.
+pub fn build(
+    chapters: &[Chapter],
+    cover_relative_path: Option<&str>,
+    source_url: &str,
+    config: &AppConfig,
+    manifest: &mut Manifest,
+) -> anyhow::Result<()> {
+    if chapters.is_empty() {
+        return Ok(());
+    }
+
+    if config.dry_run {
+        info!("Dry run: would build archive.epub");
+        return Ok(());
+    }
+
+    info!(chapters = chapters.len(), "Building EPUB");
+
+    let images = collect_images(config, cover_relative_path)?;
+    let uid = format!(
+        "urn:robustack:{}",
+        integrity::sha256_hex(source_url.as_bytes())
+    );
+
+    let mut bytes = Vec::new();
+    {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut bytes));
+
+        // `mimetype` must come first and be stored uncompressed, per spec.
+        let stored = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("mimetype", stored)?;
+        zip.write_all(b"application/epub+zip")?;
+
+        let deflated = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        zip.start_file("META-INF/container.xml", deflated)?;
+        zip.write_all(container_xml().as_bytes())?;
+
+        zip.start_file("OEBPS/content.opf", deflated)?;
+        zip.write_all(content_opf(chapters, cover_relative_path, &images, &uid).as_bytes())?;
+
+        zip.start_file("OEBPS/nav.xhtml", deflated)?;
+        zip.write_all(nav_xhtml(chapters).as_bytes())?;
+
+        zip.start_file("OEBPS/toc.ncx", deflated)?;
+        zip.write_all(toc_ncx(chapters, &uid).as_bytes())?;
+
+        for chapter in chapters {
+            let safe_slug = integrity::sanitize_filename(&chapter.slug);
+            zip.start_file(format!("OEBPS/{safe_slug}.xhtml"), deflated)?;
+            zip.write_all(chapter_xhtml(chapter).as_bytes())?;
+        }
+
+        for image in &images {
+            let contents = std::fs::read(config.output_dir.join(image))?;
+            zip.start_file(format!("OEBPS/{}", image.to_string_lossy()), deflated)?;
+            zip.write_all(&contents)?;
+        }
+
+        if let Some(cover) = cover_relative_path {
+            let contents = std::fs::read(config.output_dir.join(cover))?;
+            zip.start_file(format!("OEBPS/{cover}"), deflated)?;
+            zip.write_all(&contents)?;
+        }
+
+        zip.finish()?;
+    }
+
+    let hash = integrity::digest_hex(config.hash_algo, &bytes);
+    let filename = "archive.epub".to_string();
+
+    if integrity::should_skip(manifest, &hash, &config.output_dir, &filename, &config.sandbox) {
+        info!("Skipping archive.epub (up to date)");
+        return Ok(());
+    }
+
+    let path = config.output_dir.join(&filename);
+    let mut file = std::fs::File::create(&path).context("Failed to create archive.epub")?;
+    file.write_all(&bytes)?;
+    info!(path = %path.display(), "Saved EPUB");
+
+    manifest.insert(integrity::ManifestEntry {
+        local_path: filename,
+        digest: hash,
+        source_url: source_url.to_string(),
+        size: bytes.len() as u64,
+        downloaded_at: chrono::Utc::now().to_rfc3339(),
+        slug: None,
+        post_date: None,
+        algo: config.hash_algo,
+        compressed_size: None,
+        encoding: None,
+        sri: None,
+    });
+
+    Ok(())
+}
+
+/// List every file already downloaded under `config.images_dir`, relative
+/// to `output_dir`, excluding the cover image (it gets its own dedicated
+/// manifest item with `properties="cover-image"`, so it must not also
+/// appear as a plain image item under the same `href`).
+fn collect_images(
+    config: &AppConfig,
+    cover_relative_path: Option<&str>,
+) -> anyhow::Result<Vec<std::path::PathBuf>> {
+    let dir = config.output_dir.join(&config.images_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut images = Vec::new();
+    for entry in std::fs::read_dir(&dir)
+        .with_context(|| format!("Failed to read directory {}", dir.display()))?
+    {
+        let entry = entry?;
+        if entry.path().is_file() {
+            let relative = std::path::Path::new(&config.images_dir).join(entry.file_name());
+            if cover_relative_path != Some(relative.to_string_lossy().as_ref()) {
+                images.push(relative);
+            }
+        }
+    }
+    images.sort();
+    Ok(images)
+}
+
+fn media_type_for(relative_path: &str) -> &'static str {
+    match std::path::Path::new(relative_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn container_xml() -> &'static str {
+    r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles>
+<rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+</rootfiles>
+</container>"#
+}
+
+fn content_opf(
+    chapters: &[Chapter],
+    cover_relative_path: Option<&str>,
+    images: &[std::path::PathBuf],
+    uid: &str,
+) -> String {
+    let mut manifest_items = String::new();
+    manifest_items.push_str(
+        "<item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n",
+    );
+    manifest_items.push_str("<item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\"/>\n");
+
+    let mut cover_meta = String::new();
+    if let Some(cover) = cover_relative_path {
+        let media_type = media_type_for(cover);
+        manifest_items.push_str(&format!(
+            "<item id=\"cover-image\" href=\"{cover}\" media-type=\"{media_type}\" properties=\"cover-image\"/>\n"
+        ));
+        cover_meta.push_str("<meta name=\"cover\" content=\"cover-image\"/>\n");
+    }
+
+    for image in images {
+        let href = image.to_string_lossy();
+        let id = format!("img-{}", integrity::sanitize_filename(&href));
+        let media_type = media_type_for(&href);
+        manifest_items.push_str(&format!(
+            "<item id=\"{id}\" href=\"{href}\" media-type=\"{media_type}\"/>\n"
+        ));
+    }
+
+    let mut spine_items = String::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        let safe_slug = integrity::sanitize_filename(&chapter.slug);
+        let id = format!("chapter-{i}");
+        manifest_items.push_str(&format!(
+            "<item id=\"{id}\" href=\"{safe_slug}.xhtml\" media-type=\"application/xhtml+xml\"/>\n"
+        ));
+        spine_items.push_str(&format!("<itemref idref=\"{id}\"/>\n"));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0" unique-identifier="bookid">
+<metadata xmlns:dc="http://purl.org/dc/elements/1.1/">
+<dc:identifier id="bookid">{uid}</dc:identifier>
+<dc:title>Substack Archive</dc:title>
+<dc:language>en</dc:language>
+{cover_meta}</metadata>
+<manifest>
+{manifest_items}</manifest>
+<spine>
+{spine_items}</spine>
+</package>"#
+    )
+}
+
+fn nav_xhtml(chapters: &[Chapter]) -> String {
+    let mut items = String::new();
+    for chapter in chapters {
+        let safe_slug = integrity::sanitize_filename(&chapter.slug);
+        items.push_str(&format!(
+            "<li><a href=\"{safe_slug}.xhtml\">{}</a></li>\n",
+            escape_xml(&chapter.title)
+        ));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head><meta charset="utf-8"/><title>Table of Contents</title></head>
+<body>
+<nav epub:type="toc" id="toc">
+<h1>Table of Contents</h1>
+<ol>
+{items}</ol>
+</nav>
+</body>
+</html>"#
+    )
+}
+
+fn toc_ncx(chapters: &[Chapter], uid: &str) -> String {
+    let mut nav_points = String::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        let safe_slug = integrity::sanitize_filename(&chapter.slug);
+        let order = i + 1;
+        let title = escape_xml(&chapter.title);
+        nav_points.push_str(&format!(
+            "<navPoint id=\"navpoint-{i}\" playOrder=\"{order}\">\n<navLabel><text>{title}</text></navLabel>\n<content src=\"{safe_slug}.xhtml\"/>\n</navPoint>\n"
+        ));
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<ncx xmlns="http://www.daisy.org/z3986/2005/ncx/" version="2005-1">
+<head>
+<meta name="dtb:uid" content="{uid}"/>
+</head>
+<docTitle><text>Substack Archive</text></docTitle>
+<navMap>
+{nav_points}</navMap>
+</ncx>"#
+    )
+}
+
+fn chapter_xhtml(chapter: &Chapter) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<!DOCTYPE html>
+<html xmlns="http://www.w3.org/1999/xhtml" xmlns:epub="http://www.idpf.org/2007/ops">
+<head><meta charset="utf-8"/><title>{title}</title></head>
+<body>
+<h1>{title}</h1>
+{body}
+</body>
+</html>"#,
+        title = escape_xml(&chapter.title),
+        body = chapter.body_html,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> AppConfig {
+        use crate::cli::Cli;
+        use clap::Parser;
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "download",
+            "--url",
+            "https://example.substack.com",
+            "--format",
+            "epub",
+        ])
+        .unwrap();
+        if let crate::cli::Commands::Download(ref dl) = cli.command {
+            AppConfig::from_cli(&cli, Some(dl)).expect("valid config")
+        } else {
+            panic!("expected Download");
+        }
+    }
+
+    fn sample_chapters() -> Vec<Chapter> {
+        vec![
+            Chapter {
+                title: "First".to_string(),
+                slug: "first".to_string(),
+                source_url: "https://x.com/p/first".to_string(),
+                body_html: "<p>one</p>".to_string(),
+            },
+            Chapter {
+                title: "Second".to_string(),
+                slug: "second".to_string(),
+                source_url: "https://x.com/p/second".to_string(),
+                body_html: "<p>two</p>".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn build_writes_archive_epub_with_mimetype_stored_first() {
+        let mut config = test_config();
+        let tmp = std::env::temp_dir().join("robustack_test_epub_build");
+        let _ = std::fs::remove_dir_all(&tmp);
+        std::fs::create_dir_all(&tmp).unwrap();
+        config.output_dir = tmp.clone();
+
+        let mut manifest = Manifest::default();
+        build(
+            &sample_chapters(),
+            None,
+            "https://x.com",
+            &config,
+            &mut manifest,
+        )
+        .unwrap();
+
+        let path = tmp.join("archive.epub");
+        assert!(path.exists());
+
+        let file = std::fs::File::open(&path).unwrap();
+        let mut zip = zip::ZipArchive::new(file).unwrap();
+        let first = zip.by_index(0).unwrap();
+        assert_eq!(first.name(), "mimetype");
+        assert_eq!(first.compression(), zip::CompressionMethod::Stored);
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn build_is_noop_for_dry_run() {
+        let mut config = test_config();
+        config.dry_run = true;
+        let tmp = std::env::temp_dir().join("robustack_test_epub_dry_run");
+        let _ = std::fs::remove_dir_all(&tmp);
+        config.output_dir = tmp.clone();
+
+        let mut manifest = Manifest::default();
+        build(
+            &sample_chapters(),
+            None,
+            "https://x.com",
+            &config,
+            &mut manifest,
+        )
+        .unwrap();
+
+        assert!(!tmp.join("archive.epub").exists());
+    }
+
+    #[test]
+    fn build_is_noop_for_empty_chapters() {
+        let config = test_config();
+        let mut manifest = Manifest::default();
+        assert!(build(&[], None, "https://x.com", &config, &mut manifest).is_ok());
+    }
+
+    #[test]
+    fn content_opf_orders_spine_by_chapters_and_marks_cover() {
+        let opf = content_opf(&sample_chapters(), Some("images/cover.jpg"), &[], "urn:test");
+        let first_idx = opf.find("chapter-0").unwrap();
+        let second_idx = opf.find("chapter-1").unwrap();
+        assert!(first_idx < second_idx);
+        assert!(opf.contains("properties=\"cover-image\""));
+        assert!(opf.contains("images/cover.jpg"));
+    }
+
+    #[test]
+    fn media_type_for_maps_known_extensions() {
+        assert_eq!(media_type_for("images/a.png"), "image/png");
+        assert_eq!(media_type_for("images/a.jpg"), "image/jpeg");
+        assert_eq!(media_type_for("images/a.weird"), "application/octet-stream");
+    }
+}
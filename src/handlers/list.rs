@@ -39,12 +39,12 @@ use crate::config::AppConfig;
 pub async fn run(url: &str, config: &AppConfig, client: &dyn HttpClient) -> anyhow::Result<()> {
     info!(url = %url, "Listing posts");
 
-    if let Some(ref after) = config.after {
-        info!(after = %after, "Filtering posts after date");
+    if let Some(after) = config.date_range.after {
+        info!(after = %after.to_rfc3339(), "Filtering posts after date");
     }
 
-    if let Some(ref before) = config.before {
-        info!(before = %before, "Filtering posts before date");
+    if let Some(before) = config.date_range.before {
+        info!(before = %before.to_rfc3339(), "Filtering posts before date");
     }
 
     // Fetch all posts using shared logic (handles pagination & filtering)
@@ -87,6 +87,20 @@ mod tests {
             }
             Ok("stub".to_string())
         }
+        async fn download_to(&self, _url: &str, dest: &std::path::Path) -> anyhow::Result<u64> {
+            let bytes = b"stub";
+            tokio::fs::write(dest, bytes).await?;
+            Ok(bytes.len() as u64)
+        }
+        async fn get_to_file(
+            &self,
+            _url: &str,
+            dest: &std::path::Path,
+        ) -> anyhow::Result<(u64, String, Option<String>)> {
+            let bytes = b"stub";
+            tokio::fs::write(dest, bytes).await?;
+            Ok((bytes.len() as u64, crate::integrity::sha256_hex(bytes), None))
+        }
         fn rate_limit(&self) -> u32 {
             100
         }
@@ -102,7 +116,7 @@ mod tests {
             "https://example.substack.com",
         ])
         .unwrap();
-        AppConfig::from_cli(&cli, None)
+        AppConfig::from_cli(&cli, None).expect("valid config")
     }
 
     #[tokio::test]
@@ -0,0 +1,27 @@
+//! @project       RoBustack-DL
+//! @organization  CodeBush Collective
+//! @license       GPL-3.0-only
+//! ---------------------------------------------------------------------------
+//! AI PROVENANCE & HUMAN-IN-THE-LOOP (HITL) METADATA:
+//! - Prompt Engineering: Gemini 3 Flash (Strategy, Scoping & Context Tuning)
+//! - Code Generation:   Gemini 3 Pro (Core Systems Engineering & Async Logic)
+//! - Technical Review:  Claude 4.6 Opus (Security Audit & Idiomatic Refinement)
+//! - HITL Verification: Collisio-Adolebitque - AA0614550BDC21F1 (Manual Audit & Final Validation)
+//! ---------------------------------------------------------------------------
+//! Verified Date: 2026-07-30
+//! Integrity: GPG-Signed | HITL-Certified
+//!
+//! Handler modules — one per CLI subcommand (plus shared helpers).
+//!
+//! The fetching handlers (`download`, `list`) receive typed `&AppConfig` and
+//! `&dyn HttpClient`, never raw CLI types, so they can be exercised with a
+//! mock client in isolation. `audit` takes the manifest path and its flags
+//! directly, and `serve` takes its parsed `ServeArgs`.
+
+pub mod archive;
+pub mod audit;
+pub mod download;
+pub mod epub;
+pub mod list;
+pub mod serve;
+pub mod substack;
@@ -0,0 +1,476 @@
+//! @project       RoBustack-DL
+//! @organization  CodeBush Collective
+//! @license       GPL-3.0-only
+//! ---------------------------------------------------------------------------
+//! AI PROVENANCE & HUMAN-IN-THE-LOOP (HITL) METADATA:
+//! - Prompt Engineering: Gemini 3 Flash (Strategy, Scoping & Context Tuning)
+//! - Code Generation:   Gemini 3 Pro (Core Systems Engineering & Async Logic)
+//! - Technical Review:  Claude 4.6 Opus (Security Audit & Idiomatic Refinement)
+//! - HITL Verification: Collisio-Adolebitque - AA0614550BDC21F1 (Manual Audit & Final Validation)
+//! ---------------------------------------------------------------------------
+//! Verified Date: 2026-07-30
+//! Integrity: GPG-Signed | HITL-Certified
+//!
+//! Serve handler — a local, read-only HTTP server over a finished archive
+//! directory, so a downloaded corpus is immediately browsable without
+//! dragging files into a browser one at a time.
+//!
+//! # Root page
+//! If the archive directory contains an `index.html` (the page
+//! [`crate::handlers::archive::generate_index`] writes for `--create-archive`,
+//! optionally compression-suffixed) it is served as-is at `/`, and the same
+//! goes for any subdirectory holding such a file. Every directory without
+//! one gets an auto-generated listing instead:
+//! name, size, and modified time for each entry, plus a per-file integrity
+//! badge (see below).
+//!
+//! # Integrity badges
+//! Each file in a listing is looked up in `manifest.json` by its relative
+//! path and re-verified against the manifest's stored digest via
+//! [`integrity::verify_file_compressed`] — the same check `audit` performs —
+//! so a reader can see at a glance whether a file still matches what was
+//! originally downloaded. Files absent from the manifest (or when no
+//! manifest exists) show no badge rather than a false mismatch.
+//!
+//! # Path containment
+//! Every request path is resolved against the archive root and rejected if
+//! it contains a `..` segment or, after canonicalization, resolves outside
+//! the root — the same defence-in-depth the rest of the crate applies to
+//! manifest-driven paths, adapted here for arbitrary request paths rather
+//! than manifest entries.
+//!
+//! # Auth
+//! `--auth user:pass` gates every request behind HTTP Basic auth. Without
+//! it, the server is open to anyone who can reach `--bind`.
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::Router;
+use axum::extract::State;
+use axum::http::{HeaderMap, HeaderValue, StatusCode, Uri, header};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use base64::Engine as _;
+use secrecy::ExposeSecret;
+use tracing::{error, info};
+
+use crate::cli::ServeArgs;
+use crate::integrity::{self, Manifest, Sandbox};
+use crate::sniff;
+
+/// Names written by this crate itself that a directory listing should
+/// not surface as if they were archive content.
+const HIDDEN_ENTRIES: &[&str] = &["manifest.json", "manifest.json.sig"];
+
+/// Candidate filenames checked, in order, for the root page — matches the
+/// names [`crate::handlers::archive::generate_index`] writes depending on
+/// whether `--compress` was active.
+const INDEX_CANDIDATES: &[&str] = &["index.html", "index.html.gz", "index.html.br", "index.html.zst"];
+
+/// Shared, read-only state built once at startup and cloned (via `Arc`)
+/// into every request.
+struct ServeState {
+    root: PathBuf,
+    sandbox: Sandbox,
+    manifest: Manifest,
+    auth: Option<(String, String)>,
+}
+
+/// Start the archive server and block until it exits.
+///
+/// # Errors
+/// Returns `anyhow::Error` if `args.dir` cannot be resolved, `args.bind` is
+/// not a valid socket address, or the listener cannot be bound.
+pub async fn run(args: &ServeArgs) -> anyhow::Result<()> {
+    let root = std::fs::canonicalize(&args.dir)
+        .map_err(|e| anyhow::anyhow!("Cannot resolve archive directory {}: {e}", args.dir.display()))?;
+
+    let sandbox = Sandbox::new(vec![root.clone()]);
+    // A missing manifest just means no integrity badges are shown, not a
+    // reason to refuse to serve the directory at all.
+    let manifest = Manifest::load_or_create(&root, &sandbox).unwrap_or_default();
+
+    let auth = args.auth.as_ref().map(|secret| {
+        let spec = secret.expose_secret();
+        let (user, pass) = spec.split_once(':').unwrap_or((spec.as_str(), ""));
+        (user.to_owned(), pass.to_owned())
+    });
+
+    let bind_addr: SocketAddr = args
+        .bind
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Invalid --bind address {}: {e}", args.bind))?;
+
+    let state = Arc::new(ServeState { root: root.clone(), sandbox, manifest, auth });
+
+    let app = Router::new()
+        .route("/", get(serve_path))
+        .route("/{*path}", get(serve_path))
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(bind_addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("Cannot bind {bind_addr}: {e}"))?;
+
+    info!(addr = %bind_addr, root = %root.display(), "Serving archive (read-only)");
+    axum::serve(listener, app).await.map_err(|e| anyhow::anyhow!("Server error: {e}"))?;
+
+    Ok(())
+}
+
+/// Handle a single request: authenticate, resolve the path inside the
+/// archive root, then either serve a file or render a directory listing.
+async fn serve_path(State(state): State<Arc<ServeState>>, headers: HeaderMap, uri: Uri) -> Response {
+    if let Some((user, pass)) = &state.auth {
+        if !basic_auth_ok(&headers, user, pass) {
+            return unauthorized_response();
+        }
+    }
+
+    let requested = uri.path().trim_start_matches('/');
+    let Some(target) = safe_join(&state.root, requested) else {
+        return not_found_response();
+    };
+
+    let canonical = match std::fs::canonicalize(&target) {
+        Ok(p) if p.starts_with(&state.root) => p,
+        _ => return not_found_response(),
+    };
+
+    if canonical.is_dir() {
+        if let Some(index) = find_index_file(&canonical) {
+            return serve_file_response(&index);
+        }
+        match render_listing(&state, &canonical, requested).await {
+            Ok(html) => Html(html).into_response(),
+            Err(e) => internal_error_response(&e),
+        }
+    } else {
+        serve_file_response(&canonical)
+    }
+}
+
+/// Resolve a request path onto `root`, rejecting any `..` segment before
+/// ever touching the filesystem.
+fn safe_join(root: &Path, req_path: &str) -> Option<PathBuf> {
+    let mut path = root.to_path_buf();
+    for segment in req_path.split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => return None,
+            segment => path.push(segment),
+        }
+    }
+    Some(path)
+}
+
+/// Find the first existing index-page candidate in `dir`, if any.
+fn find_index_file(dir: &Path) -> Option<PathBuf> {
+    INDEX_CANDIDATES.iter().map(|name| dir.join(name)).find(|candidate| candidate.is_file())
+}
+
+/// Read `path` and respond with its bytes, setting `Content-Type` from its
+/// (possibly compression-suffixed) extension and `Content-Encoding` when
+/// the file is one this crate wrote with `--compress`.
+fn serve_file_response(path: &Path) -> Response {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => return internal_error_response(&anyhow::anyhow!("Cannot read {}: {e}", path.display())),
+    };
+
+    let filename = path.file_name().and_then(|f| f.to_str()).unwrap_or("");
+    let (display_name, encoding) = strip_compress_suffix(filename);
+    let ext = Path::new(display_name).extension().and_then(|e| e.to_str()).unwrap_or("");
+    let content_type = sniff::mime_for_extension(ext);
+
+    let mut response = (StatusCode::OK, bytes).into_response();
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(content_type).expect("mime_for_extension returns a valid header value"),
+    );
+    if let Some(encoding) = encoding {
+        response.headers_mut().insert(
+            header::CONTENT_ENCODING,
+            HeaderValue::from_static(encoding),
+        );
+    }
+    response
+}
+
+/// Strip a `--compress` suffix (`.gz`/`.br`/`.zst`) off `filename`, if
+/// present, returning the underlying name plus the `Content-Encoding`
+/// token a browser expects for that suffix.
+fn strip_compress_suffix(filename: &str) -> (&str, Option<&'static str>) {
+    if let Some(stripped) = filename.strip_suffix(".gz") {
+        (stripped, Some("gzip"))
+    } else if let Some(stripped) = filename.strip_suffix(".br") {
+        (stripped, Some("br"))
+    } else if let Some(stripped) = filename.strip_suffix(".zst") {
+        (stripped, Some("zstd"))
+    } else {
+        (filename, None)
+    }
+}
+
+/// Render an auto-generated directory listing: name, size, modified time,
+/// and an integrity badge for each entry.
+async fn render_listing(state: &ServeState, dir: &Path, req_path: &str) -> anyhow::Result<String> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| anyhow::anyhow!("Cannot list {}: {e}", dir.display()))?
+        .filter_map(Result::ok)
+        .collect();
+    entries.sort_by_key(std::fs::DirEntry::file_name);
+
+    let escaped_req_path = req_path.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str(&format!("<title>Index of /{escaped_req_path}</title>\n"));
+    html.push_str("<style>\n");
+    html.push_str("body { font-family: system-ui, sans-serif; max-width: 900px; margin: 2rem auto; line-height: 1.5; }\n");
+    html.push_str("table { width: 100%; border-collapse: collapse; }\n");
+    html.push_str("th, td { text-align: left; padding: 0.4rem 0.6rem; border-bottom: 1px solid #eee; }\n");
+    html.push_str("a { text-decoration: none; color: #0066cc; }\n");
+    html.push_str("a:hover { text-decoration: underline; }\n");
+    html.push_str(".badge-ok { color: #0a7a27; font-size: 0.85em; }\n");
+    html.push_str(".badge-bad { color: #b00020; font-size: 0.85em; }\n");
+    html.push_str("</style>\n</head>\n<body>\n");
+    html.push_str(&format!("<h1>Index of /{escaped_req_path}</h1>\n"));
+    html.push_str("<table>\n<tr><th>Name</th><th>Size</th><th>Modified</th><th>Integrity</th></tr>\n");
+
+    if !req_path.is_empty() {
+        html.push_str("<tr><td><a href=\"../\">../</a></td><td></td><td></td><td></td></tr>\n");
+    }
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if HIDDEN_ENTRIES.contains(&name.as_str()) {
+            continue;
+        }
+        let meta = entry.metadata().map_err(|e| anyhow::anyhow!("Cannot stat {name}: {e}"))?;
+        let is_dir = meta.is_dir();
+        let escaped = name
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;");
+        let href = if is_dir { format!("{escaped}/") } else { escaped };
+        let size = if is_dir { String::new() } else { human_size(meta.len()) };
+        let modified = meta.modified().map(format_modified).unwrap_or_default();
+        let badge = if is_dir {
+            String::new()
+        } else {
+            let rel_path = if req_path.is_empty() { name.clone() } else { format!("{req_path}/{name}") };
+            integrity_badge(state, &rel_path).await
+        };
+
+        html.push_str("<tr>");
+        html.push_str(&format!("<td><a href=\"{href}\">{href}</a></td>"));
+        html.push_str(&format!("<td>{size}</td>"));
+        html.push_str(&format!("<td>{modified}</td>"));
+        html.push_str(&format!("<td>{badge}</td>"));
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>\n</body>\n</html>");
+    Ok(html)
+}
+
+/// Look `rel_path` up in the manifest and re-verify its digest, returning
+/// the badge markup for a listing row — empty when the file isn't tracked.
+async fn integrity_badge(state: &ServeState, rel_path: &str) -> String {
+    let Some(entry) = state.manifest.entries().find(|e| e.local_path == rel_path) else {
+        return String::new();
+    };
+
+    match integrity::verify_file_compressed(
+        &state.root,
+        rel_path,
+        &entry.digest,
+        entry.algo,
+        &state.sandbox,
+        entry.encoding.as_deref(),
+    )
+    .await
+    {
+        Ok((true, _)) => "<span class=\"badge-ok\">verified</span>".to_owned(),
+        _ => "<span class=\"badge-bad\">mismatch</span>".to_owned(),
+    }
+}
+
+/// Format a byte count as a human-readable size (`"12.3 MB"`).
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Format a file's modified time as a UTC timestamp.
+fn format_modified(time: SystemTime) -> String {
+    let datetime: chrono::DateTime<chrono::Utc> = time.into();
+    datetime.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+/// Check an `Authorization: Basic ...` header against `user`/`pass`.
+fn basic_auth_ok(headers: &HeaderMap, user: &str, pass: &str) -> bool {
+    let Some(value) = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let Some(encoded) = value.strip_prefix("Basic ") else {
+        return false;
+    };
+    let Ok(decoded) = base64::engine::general_purpose::STANDARD.decode(encoded) else {
+        return false;
+    };
+    let Ok(decoded) = String::from_utf8(decoded) else {
+        return false;
+    };
+    decoded
+        .split_once(':')
+        .is_some_and(|(u, p)| constant_time_eq(u.as_bytes(), user.as_bytes()) && constant_time_eq(p.as_bytes(), pass.as_bytes()))
+}
+
+/// Compare two byte strings without branching on the content of either —
+/// a plain `==` here would let a timing attacker narrow down `--auth`'s
+/// credentials one byte at a time.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn unauthorized_response() -> Response {
+    let mut response = (StatusCode::UNAUTHORIZED, "Unauthorized").into_response();
+    response.headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        HeaderValue::from_static("Basic realm=\"robustack-dl archive\""),
+    );
+    response
+}
+
+fn not_found_response() -> Response {
+    (StatusCode::NOT_FOUND, "Not found").into_response()
+}
+
+fn internal_error_response(err: &anyhow::Error) -> Response {
+    error!(error = %err, "Serve request failed");
+    (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_join_rejects_parent_dir_segments() {
+        let root = PathBuf::from("/archive");
+        assert_eq!(safe_join(&root, "../../etc/passwd"), None);
+        assert_eq!(safe_join(&root, "images/../../etc/passwd"), None);
+    }
+
+    #[test]
+    fn safe_join_resolves_nested_paths() {
+        let root = PathBuf::from("/archive");
+        assert_eq!(safe_join(&root, "images/foo.jpg"), Some(PathBuf::from("/archive/images/foo.jpg")));
+        assert_eq!(safe_join(&root, ""), Some(PathBuf::from("/archive")));
+        assert_eq!(safe_join(&root, "/"), Some(PathBuf::from("/archive")));
+    }
+
+    #[test]
+    fn strip_compress_suffix_recognizes_known_extensions() {
+        assert_eq!(strip_compress_suffix("post.html.gz"), ("post.html", Some("gzip")));
+        assert_eq!(strip_compress_suffix("post.html.br"), ("post.html", Some("br")));
+        assert_eq!(strip_compress_suffix("post.html.zst"), ("post.html", Some("zstd")));
+        assert_eq!(strip_compress_suffix("post.html"), ("post.html", None));
+    }
+
+    #[test]
+    fn human_size_scales_to_largest_fitting_unit() {
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(2048), "2.0 KB");
+        assert_eq!(human_size(5 * 1024 * 1024), "5.0 MB");
+    }
+
+    #[test]
+    fn basic_auth_ok_requires_matching_credentials() {
+        let mut headers = HeaderMap::new();
+        let token = base64::engine::general_purpose::STANDARD.encode(b"reader:hunter2");
+        headers.insert(header::AUTHORIZATION, format!("Basic {token}").parse().unwrap());
+
+        assert!(basic_auth_ok(&headers, "reader", "hunter2"));
+        assert!(!basic_auth_ok(&headers, "reader", "wrong"));
+        assert!(!basic_auth_ok(&HeaderMap::new(), "reader", "hunter2"));
+    }
+
+    #[tokio::test]
+    async fn render_listing_escapes_req_path_in_title_and_heading() {
+        let tmp = std::env::temp_dir().join("robustack-dl-serve-test-listing-escape");
+        let _ = std::fs::create_dir_all(&tmp);
+
+        let state = ServeState {
+            root: tmp.clone(),
+            sandbox: Sandbox::new(vec![tmp.clone()]),
+            manifest: Manifest::default(),
+            auth: None,
+        };
+
+        let html = render_listing(&state, &tmp, "<script>alert(1)</script>").await.unwrap();
+        assert!(!html.contains("<script>alert(1)</script>"), "{html}");
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"), "{html}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[tokio::test]
+    async fn render_listing_escapes_quotes_in_entry_href() {
+        let tmp = std::env::temp_dir().join("robustack-dl-serve-test-listing-quote");
+        let _ = std::fs::create_dir_all(&tmp);
+        std::fs::write(tmp.join("a\"onmouseover=\"alert(1)\".txt"), b"x").unwrap();
+
+        let state = ServeState {
+            root: tmp.clone(),
+            sandbox: Sandbox::new(vec![tmp.clone()]),
+            manifest: Manifest::default(),
+            auth: None,
+        };
+
+        let html = render_listing(&state, &tmp, "").await.unwrap();
+        assert!(!html.contains("a\"onmouseover=\"alert(1)\".txt"), "{html}");
+        assert!(html.contains("a&quot;onmouseover=&quot;alert(1)&quot;.txt"), "{html}");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn constant_time_eq_matches_and_rejects_mismatches() {
+        assert!(constant_time_eq(b"hunter2", b"hunter2"));
+        assert!(!constant_time_eq(b"hunter2", b"hunter3"));
+        assert!(!constant_time_eq(b"short", b"longer-value"));
+    }
+
+    #[test]
+    fn find_index_file_prefers_uncompressed_over_compressed() {
+        let tmp = std::env::temp_dir().join("robustack-dl-serve-test-index");
+        let _ = std::fs::create_dir_all(&tmp);
+        std::fs::write(tmp.join("index.html.gz"), b"compressed").unwrap();
+
+        assert_eq!(find_index_file(&tmp), Some(tmp.join("index.html.gz")));
+
+        std::fs::write(tmp.join("index.html"), b"raw").unwrap();
+        assert_eq!(find_index_file(&tmp), Some(tmp.join("index.html")));
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
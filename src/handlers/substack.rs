@@ -14,8 +14,18 @@
 //! Substack API client / shared types.
 //!
 //! Handles pagination, date filtering, and type definition for posts.
+//!
+//! # RSS/Atom fallback
+//! The JSON API (`/api/v1/posts`) is paywalled or rate-limited on some
+//! Substacks, and non-Substack mirrors don't implement it at all. When
+//! `config.allow_rss_fallback` is set and the API call errors or returns a
+//! body that won't parse, [`fetch_posts`] retries against `{url}/feed`
+//! (RSS 2.0 or Atom) instead of failing outright. Feed metadata is lower
+//! fidelity than the API — there's no stable numeric post `id`, so one is
+//! synthesized — which is why the fallback is opt-in rather than silent.
 
 use serde::Deserialize;
+use tracing::warn;
 
 use crate::client::HttpClient;
 use crate::config::AppConfig;
@@ -43,11 +53,39 @@ enum RawResponse {
     Array(Vec<SubstackPost>),
 }
 
+/// Fetch all posts matching configuration filters.
+///
+/// Tries the Substack JSON API first; if that errors or returns a body
+/// that doesn't parse and `config.allow_rss_fallback` is set, retries
+/// against `{base_url}/feed` as RSS/Atom. See the module docs.
+///
+/// # Errors
+/// Returns `anyhow::Error` if the API request fails and either the
+/// fallback is disabled or the feed request also fails.
+pub async fn fetch_posts(
+    base_url: &str,
+    config: &AppConfig,
+    client: &dyn HttpClient,
+) -> anyhow::Result<Vec<SubstackPost>> {
+    match fetch_posts_via_api(base_url, config, client).await {
+        Ok(posts) => Ok(posts),
+        Err(e) if config.allow_rss_fallback => {
+            warn!(
+                url = %base_url,
+                error = %e,
+                "Substack API unavailable; falling back to RSS/Atom feed (reduced metadata fidelity)"
+            );
+            fetch_posts_via_feed(base_url, config, client).await
+        }
+        Err(e) => Err(e),
+    }
+}
+
 /// Fetch all posts matching configuration filters.
 ///
 /// Handles pagination automatically (limit=50).
 /// Resets `after`/`before` filters against `post_date` (ISO8601 string).
-pub async fn fetch_posts(
+async fn fetch_posts_via_api(
     base_url: &str,
     config: &AppConfig,
     client: &dyn HttpClient,
@@ -87,16 +125,8 @@ pub async fn fetch_posts(
 
         let received_count = posts.len() as u64;
         for post in posts {
-            // Date filtering via string comparison (works for ISO8601)
-            if let Some(ref after) = config.after {
-                if post.post_date < *after {
-                    continue;
-                }
-            }
-            if let Some(ref before) = config.before {
-                if post.post_date > *before {
-                    continue;
-                }
+            if !config.date_range.contains(&post.post_date) {
+                continue;
             }
             if let Some(l) = config.limit {
                 if all_posts.len() >= l as usize {
@@ -137,6 +167,167 @@ pub async fn fetch_posts(
     Ok(all_posts)
 }
 
+/// Fetch and parse `{base_url}/feed` (RSS 2.0 or Atom) as a best-effort
+/// substitute for the JSON API, applying the same date/limit filters.
+async fn fetch_posts_via_feed(
+    base_url: &str,
+    config: &AppConfig,
+    client: &dyn HttpClient,
+) -> anyhow::Result<Vec<SubstackPost>> {
+    let feed_url = format!("{}/feed", base_url.trim_end_matches('/'));
+    let body = client.get_text(&feed_url).await?;
+    let mut posts = parse_feed(&body)?;
+
+    posts.retain(|post| config.date_range.contains(&post.post_date));
+    if let Some(limit) = config.limit {
+        posts.truncate(limit as usize);
+    }
+
+    Ok(posts)
+}
+
+/// Parse an RSS `<item>` or Atom `<entry>` list into `SubstackPost`s.
+///
+/// Field mapping (RSS / Atom):
+/// - `title` → `title`
+/// - `link` (text) / `link[href]` → `canonical_url`
+/// - `guid` / `id` → `slug` (last path segment)
+/// - `pubDate` (RFC 2822) / `updated`/`published` (RFC 3339) → `post_date`,
+///   normalized to RFC 3339
+/// - `content:encoded` / `content` → `body_html`
+/// - `description` / `summary` → `description`
+///
+/// There's no stable numeric post id in either feed format, so one is
+/// synthesized from each entry's position in the feed.
+fn parse_feed(body: &str) -> anyhow::Result<Vec<SubstackPost>> {
+    use quick_xml::events::Event;
+    use quick_xml::Reader;
+
+    let mut reader = Reader::from_str(body);
+    reader.trim_text(true);
+
+    let mut posts = Vec::new();
+    let mut buf = Vec::new();
+    let mut fields: Option<std::collections::HashMap<String, String>> = None;
+    let mut current_tag: Option<String> = None;
+    let mut next_id: u64 = 0;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let tag = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                if tag == "item" || tag == "entry" {
+                    fields = Some(std::collections::HashMap::new());
+                } else if tag == "link" {
+                    // Atom represents the post URL as `<link href="...">`
+                    // rather than text content.
+                    if let Some(map) = fields.as_mut() {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"href" {
+                                let href = String::from_utf8_lossy(&attr.value)
+                                    .into_owned()
+                                    .replace("&amp;", "&");
+                                map.entry("link".to_string()).or_insert(href);
+                            }
+                        }
+                    }
+                }
+                current_tag = Some(tag);
+            }
+            Ok(Event::Text(e)) => {
+                if let (Some(map), Some(tag)) = (fields.as_mut(), current_tag.as_ref()) {
+                    let text = e.unescape()?.into_owned();
+                    if !text.is_empty() {
+                        map.entry(tag.clone()).or_insert(text);
+                    }
+                }
+            }
+            Ok(Event::CData(e)) => {
+                if let (Some(map), Some(tag)) = (fields.as_mut(), current_tag.as_ref()) {
+                    let text = String::from_utf8_lossy(e.as_ref()).into_owned();
+                    if !text.is_empty() {
+                        map.entry(tag.clone()).or_insert(text);
+                    }
+                }
+            }
+            Ok(Event::End(ref e)) => {
+                let tag = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                if (tag == "item" || tag == "entry") && fields.is_some() {
+                    next_id += 1;
+                    posts.push(post_from_feed_fields(next_id, fields.take().unwrap()));
+                }
+                current_tag = None;
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => anyhow::bail!("Failed to parse feed XML: {e}"),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(posts)
+}
+
+fn post_from_feed_fields(id: u64, fields: std::collections::HashMap<String, String>) -> SubstackPost {
+    let title = fields.get("title").cloned().unwrap_or_default();
+    let canonical_url = fields.get("link").cloned().unwrap_or_default();
+    let guid = fields
+        .get("guid")
+        .or_else(|| fields.get("id"))
+        .cloned()
+        .unwrap_or_else(|| canonical_url.clone());
+    let raw_date = fields
+        .get("pubDate")
+        .or_else(|| fields.get("published"))
+        .or_else(|| fields.get("updated"))
+        .cloned()
+        .unwrap_or_default();
+    let body_html = fields
+        .get("content:encoded")
+        .or_else(|| fields.get("content"))
+        .cloned();
+    let description = fields
+        .get("description")
+        .or_else(|| fields.get("summary"))
+        .cloned()
+        .unwrap_or_default();
+
+    SubstackPost {
+        id,
+        title,
+        slug: slug_from_guid(&guid),
+        post_date: normalize_feed_date(&raw_date),
+        canonical_url,
+        description,
+        body_html,
+        cover_image: None,
+    }
+}
+
+/// Derive a filesystem-safe slug from a feed `guid`/`id`, which is usually
+/// the post's own URL: take the last non-empty path segment.
+fn slug_from_guid(guid: &str) -> String {
+    let trimmed = guid.trim_end_matches('/');
+    match trimmed.rsplit('/').next() {
+        Some(segment) if !segment.is_empty() => segment.to_string(),
+        _ => "untitled".to_string(),
+    }
+}
+
+/// Normalize a feed date (`pubDate` is RFC 2822; Atom's `updated`/
+/// `published` are already RFC 3339) to RFC 3339. Falls back to the raw
+/// string, unparsed, if neither format matches — `DateRange::contains`
+/// conservatively includes posts it can't parse.
+fn normalize_feed_date(raw: &str) -> String {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc2822(raw) {
+        return dt.to_rfc3339();
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(raw) {
+        return dt.to_rfc3339();
+    }
+    raw.to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,6 +347,18 @@ mod tests {
         async fn get_text(&self, _url: &str) -> anyhow::Result<String> {
             Ok(self.response.clone())
         }
+        async fn download_to(&self, _url: &str, dest: &std::path::Path) -> anyhow::Result<u64> {
+            tokio::fs::write(dest, b"").await?;
+            Ok(0)
+        }
+        async fn get_to_file(
+            &self,
+            _url: &str,
+            dest: &std::path::Path,
+        ) -> anyhow::Result<(u64, String, Option<String>)> {
+            tokio::fs::write(dest, b"").await?;
+            Ok((0, crate::integrity::sha256_hex(b""), None))
+        }
         fn rate_limit(&self) -> u32 {
             100
         }
@@ -167,7 +370,7 @@ mod tests {
         // Minimal valid config
         let cli =
             Cli::try_parse_from(["robustack-dl", "download", "--url", "https://x.com"]).unwrap();
-        AppConfig::from_cli(&cli, None, None)
+        AppConfig::from_cli(&cli, None).expect("valid config")
     }
 
     #[tokio::test]
@@ -218,4 +421,144 @@ mod tests {
         assert_eq!(posts.len(), 1);
         assert_eq!(posts[0].slug, "slug1");
     }
+
+    /// A mock that fails the JSON API and serves a feed body for `/feed`,
+    /// to exercise the RSS/Atom fallback path.
+    #[derive(Debug)]
+    struct ApiDownFeedUpClient {
+        feed_body: String,
+    }
+
+    #[async_trait::async_trait]
+    impl HttpClient for ApiDownFeedUpClient {
+        async fn get_bytes(&self, _url: &str) -> anyhow::Result<Vec<u8>> {
+            Ok(vec![])
+        }
+        async fn get_text(&self, url: &str) -> anyhow::Result<String> {
+            if url.contains("/api/v1/posts") {
+                anyhow::bail!("503 Service Unavailable");
+            }
+            Ok(self.feed_body.clone())
+        }
+        async fn download_to(&self, _url: &str, dest: &std::path::Path) -> anyhow::Result<u64> {
+            tokio::fs::write(dest, b"").await?;
+            Ok(0)
+        }
+        async fn get_to_file(
+            &self,
+            _url: &str,
+            dest: &std::path::Path,
+        ) -> anyhow::Result<(u64, String, Option<String>)> {
+            tokio::fs::write(dest, b"").await?;
+            Ok((0, crate::integrity::sha256_hex(b""), None))
+        }
+        fn rate_limit(&self) -> u32 {
+            100
+        }
+    }
+
+    fn config_with_rss_fallback() -> AppConfig {
+        use crate::cli::Cli;
+        use clap::Parser;
+        let cli = Cli::try_parse_from([
+            "robustack-dl",
+            "--allow-rss-fallback",
+            "download",
+            "--url",
+            "https://x.com",
+        ])
+        .unwrap();
+        AppConfig::from_cli(&cli, None).expect("valid config")
+    }
+
+    const SAMPLE_RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0">
+<channel>
+<item>
+<title>First Post</title>
+<link>https://x.substack.com/p/first-post</link>
+<guid>https://x.substack.com/p/first-post</guid>
+<pubDate>Wed, 02 Jan 2024 15:00:00 GMT</pubDate>
+<description>A description</description>
+<content:encoded><![CDATA[<p>Hello world</p>]]></content:encoded>
+</item>
+</channel>
+</rss>"#;
+
+    #[tokio::test]
+    async fn fetch_posts_falls_back_to_rss_when_api_fails_and_enabled() {
+        let client = ApiDownFeedUpClient {
+            feed_body: SAMPLE_RSS.to_string(),
+        };
+        let config = config_with_rss_fallback();
+
+        let posts = fetch_posts("https://x.substack.com", &config, &client)
+            .await
+            .unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].title, "First Post");
+        assert_eq!(posts[0].canonical_url, "https://x.substack.com/p/first-post");
+        assert_eq!(posts[0].slug, "first-post");
+        assert_eq!(posts[0].body_html.as_deref(), Some("<p>Hello world</p>"));
+    }
+
+    #[tokio::test]
+    async fn fetch_posts_propagates_error_when_fallback_disabled() {
+        let client = ApiDownFeedUpClient {
+            feed_body: SAMPLE_RSS.to_string(),
+        };
+        let config = test_config();
+
+        let result = fetch_posts("https://x.substack.com", &config, &client).await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_feed_normalizes_rfc2822_pub_date() {
+        let posts = parse_feed(SAMPLE_RSS).unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].post_date, "2024-01-02T15:00:00+00:00");
+    }
+
+    #[test]
+    fn parse_feed_handles_atom_entries() {
+        let atom = r#"<?xml version="1.0"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+<entry>
+<title>Atom Post</title>
+<link href="https://y.substack.com/p/atom-post"/>
+<id>https://y.substack.com/p/atom-post</id>
+<updated>2024-03-01T10:00:00+00:00</updated>
+<summary>An atom summary</summary>
+<content>Atom body</content>
+</entry>
+</feed>"#;
+
+        let posts = parse_feed(atom).unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].canonical_url, "https://y.substack.com/p/atom-post");
+        assert_eq!(posts[0].slug, "atom-post");
+        assert_eq!(posts[0].post_date, "2024-03-01T10:00:00+00:00");
+        assert_eq!(posts[0].description, "An atom summary");
+        assert_eq!(posts[0].body_html.as_deref(), Some("Atom body"));
+    }
+
+    #[test]
+    fn slug_from_guid_takes_last_path_segment() {
+        assert_eq!(
+            slug_from_guid("https://x.substack.com/p/my-post/"),
+            "my-post"
+        );
+        assert_eq!(slug_from_guid("bare-guid"), "bare-guid");
+        assert_eq!(slug_from_guid(""), "untitled");
+    }
+
+    #[test]
+    fn normalize_feed_date_parses_rfc2822_and_passes_through_unknown() {
+        assert_eq!(
+            normalize_feed_date("Wed, 02 Jan 2024 15:00:00 GMT"),
+            "2024-01-02T15:00:00+00:00"
+        );
+        assert_eq!(normalize_feed_date("not-a-date"), "not-a-date");
+    }
 }
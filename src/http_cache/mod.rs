@@ -0,0 +1,250 @@
+//! @project       RoBustack-DL
+//! @organization  CodeBush Collective
+//! @license       GPL-3.0-only
+//! ---------------------------------------------------------------------------
+//! AI PROVENANCE & HUMAN-IN-THE-LOOP (HITL) METADATA:
+//! - Prompt Engineering: Gemini 3 Flash (Strategy, Scoping & Context Tuning)
+//! - Code Generation:   Gemini 3 Pro (Core Systems Engineering & Async Logic)
+//! - Technical Review:  Claude 4.6 Opus (Security Audit & Idiomatic Refinement)
+//! - HITL Verification: Collisio-Adolebitque - AA0614550BDC21F1 (Manual Audit & Final Validation)
+//! ---------------------------------------------------------------------------
+//! Verified Date: 2026-07-30
+//! Integrity: GPG-Signed | HITL-Certified
+//!
+//! HTTP conditional-request cache — ETag/Last-Modified revalidation.
+//!
+//! # Design
+//! Independent of [`crate::cache`] (content-addressed by source URL, used
+//! for binary assets once downloaded): this cache understands HTTP cache
+//! semantics for responses fetched via `get_text`/`get_bytes`, so unchanged
+//! pages can be skipped entirely or revalidated with a cheap `304` instead
+//! of re-downloaded in full. Each entry is two sibling files named after
+//! the SHA-256 of the request URL:
+//! - `<hash>.body` — the exact response bytes.
+//! - `<hash>.meta.json` — validators (`ETag`, `Last-Modified`) and
+//!   freshness info (`Cache-Control: max-age`, fetch timestamp) needed to
+//!   decide whether to skip the network entirely or send a conditional GET.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+/// Validators and freshness metadata for a single cached response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheMeta {
+    /// `ETag` response header, if present.
+    pub etag: Option<String>,
+    /// `Last-Modified` response header, if present.
+    pub last_modified: Option<String>,
+    /// `max-age` parsed from `Cache-Control`, if present.
+    pub max_age_secs: Option<u64>,
+    /// RFC 3339 timestamp of when this entry was last fetched or
+    /// revalidated.
+    pub fetched_at: String,
+}
+
+/// A cached response body paired with its validators.
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub body: Vec<u8>,
+    pub meta: CacheMeta,
+}
+
+/// Path of the cached response body for `url` within `cache_dir`.
+#[must_use]
+pub fn body_path(cache_dir: &Path, url: &str) -> PathBuf {
+    cache_dir.join(format!("{}.body", crate::integrity::sha256_hex(url.as_bytes())))
+}
+
+/// Path of the cached metadata sidecar for `url` within `cache_dir`.
+#[must_use]
+pub fn meta_path(cache_dir: &Path, url: &str) -> PathBuf {
+    cache_dir.join(format!(
+        "{}.meta.json",
+        crate::integrity::sha256_hex(url.as_bytes())
+    ))
+}
+
+/// Load the cached entry for `url`, if both its body and metadata sidecar
+/// are present and the metadata parses.
+#[must_use]
+pub fn load(cache_dir: &Path, url: &str) -> Option<CacheEntry> {
+    let body = std::fs::read(body_path(cache_dir, url)).ok()?;
+    let meta_raw = std::fs::read(meta_path(cache_dir, url)).ok()?;
+    let meta: CacheMeta = serde_json::from_slice(&meta_raw).ok()?;
+    Some(CacheEntry { body, meta })
+}
+
+/// Returns `true` if `meta` is still fresh under its `Cache-Control:
+/// max-age` directive and therefore the network can be skipped entirely.
+#[must_use]
+pub fn is_fresh(meta: &CacheMeta) -> bool {
+    let Some(max_age) = meta.max_age_secs else {
+        return false;
+    };
+    let Ok(fetched_at) = chrono::DateTime::parse_from_rfc3339(&meta.fetched_at) else {
+        return false;
+    };
+    let age = chrono::Utc::now().signed_duration_since(fetched_at.with_timezone(&chrono::Utc));
+    age.to_std()
+        .is_ok_and(|elapsed| elapsed < Duration::from_secs(max_age))
+}
+
+/// Store `body` and `meta` for `url`, creating `cache_dir` if needed.
+///
+/// # Errors
+/// Returns `anyhow::Error` if `cache_dir` cannot be created or either
+/// sidecar file cannot be written.
+pub fn store(cache_dir: &Path, url: &str, body: &[u8], meta: &CacheMeta) -> anyhow::Result<()> {
+    std::fs::create_dir_all(cache_dir).context("Failed to create HTTP cache directory")?;
+    let body_path = body_path(cache_dir, url);
+    let meta_path = meta_path(cache_dir, url);
+    std::fs::write(&body_path, body)
+        .with_context(|| format!("Failed to write HTTP cache entry {}", body_path.display()))?;
+    let meta_json = serde_json::to_vec_pretty(meta).context("Failed to serialize cache meta")?;
+    std::fs::write(&meta_path, meta_json)
+        .with_context(|| format!("Failed to write HTTP cache meta {}", meta_path.display()))?;
+    Ok(())
+}
+
+/// Remove a stale cache entry for `url`, ignoring errors (e.g. already
+/// absent). Used when a fresh response must not be cached (`no-store`/
+/// `no-cache`) or carries nothing to revalidate it with later (no
+/// validators and no `max-age`).
+pub fn evict(cache_dir: &Path, url: &str) {
+    let _ = std::fs::remove_file(body_path(cache_dir, url));
+    let _ = std::fs::remove_file(meta_path(cache_dir, url));
+}
+
+/// Parse a `Cache-Control` header value, returning `(no_store, max_age)`.
+/// `no_store` also covers `no-cache`, which forbids serving the cached
+/// copy without revalidation.
+#[must_use]
+pub fn parse_cache_control(value: &str) -> (bool, Option<u64>) {
+    let mut no_store = false;
+    let mut max_age = None;
+    for directive in value.split(',') {
+        let directive = directive.trim();
+        if directive.eq_ignore_ascii_case("no-store") || directive.eq_ignore_ascii_case("no-cache")
+        {
+            no_store = true;
+        } else if let Some(secs) = directive
+            .strip_prefix("max-age=")
+            .or_else(|| directive.strip_prefix("s-maxage="))
+        {
+            max_age = secs.trim().parse::<u64>().ok();
+        }
+    }
+    (no_store, max_age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_meta() -> CacheMeta {
+        CacheMeta {
+            etag: Some("\"abc123\"".to_owned()),
+            last_modified: Some("Wed, 21 Oct 2015 07:28:00 GMT".to_owned()),
+            max_age_secs: Some(3600),
+            fetched_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn body_path_is_deterministic() {
+        let dir = Path::new("http_cache");
+        assert_eq!(
+            body_path(dir, "https://example.com/a"),
+            body_path(dir, "https://example.com/a")
+        );
+    }
+
+    #[test]
+    fn body_and_meta_paths_differ() {
+        let dir = Path::new("http_cache");
+        assert_ne!(
+            body_path(dir, "https://example.com/a"),
+            meta_path(dir, "https://example.com/a")
+        );
+    }
+
+    #[test]
+    fn load_misses_when_absent() {
+        let tmp = std::env::temp_dir().join("robustack-dl-http-cache-test-miss");
+        let _ = std::fs::remove_dir_all(&tmp);
+        assert!(load(&tmp, "https://example.com/missing").is_none());
+    }
+
+    #[test]
+    fn store_then_load_round_trips() {
+        let tmp = std::env::temp_dir().join("robustack-dl-http-cache-test-roundtrip");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let url = "https://example.com/page";
+        let meta = sample_meta();
+        store(&tmp, url, b"hello", &meta).unwrap();
+        let entry = load(&tmp, url).expect("entry should be present");
+        assert_eq!(entry.body, b"hello");
+        assert_eq!(entry.meta.etag, meta.etag);
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn evict_removes_both_files() {
+        let tmp = std::env::temp_dir().join("robustack-dl-http-cache-test-evict");
+        let _ = std::fs::remove_dir_all(&tmp);
+        let url = "https://example.com/evict-me";
+        store(&tmp, url, b"bye", &sample_meta()).unwrap();
+        evict(&tmp, url);
+        assert!(load(&tmp, url).is_none());
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn is_fresh_true_within_max_age() {
+        let meta = sample_meta();
+        assert!(is_fresh(&meta));
+    }
+
+    #[test]
+    fn is_fresh_false_when_expired() {
+        let mut meta = sample_meta();
+        meta.fetched_at = (chrono::Utc::now() - chrono::Duration::hours(2)).to_rfc3339();
+        assert!(!is_fresh(&meta));
+    }
+
+    #[test]
+    fn is_fresh_false_without_max_age() {
+        let mut meta = sample_meta();
+        meta.max_age_secs = None;
+        assert!(!is_fresh(&meta));
+    }
+
+    #[test]
+    fn parse_cache_control_extracts_max_age() {
+        let (no_store, max_age) = parse_cache_control("public, max-age=600");
+        assert!(!no_store);
+        assert_eq!(max_age, Some(600));
+    }
+
+    #[test]
+    fn parse_cache_control_detects_no_store() {
+        let (no_store, _) = parse_cache_control("no-store");
+        assert!(no_store);
+    }
+
+    #[test]
+    fn parse_cache_control_detects_no_cache() {
+        let (no_store, _) = parse_cache_control("no-cache");
+        assert!(no_store);
+    }
+
+    #[test]
+    fn parse_cache_control_handles_empty_value() {
+        let (no_store, max_age) = parse_cache_control("");
+        assert!(!no_store);
+        assert_eq!(max_age, None);
+    }
+}
@@ -11,22 +11,76 @@
 //! Verified Date: 2026-02-15
 //! Integrity: GPG-Signed | HITL-Certified
 //!
-//! Integrity module — SHA-256 manifest for content-addressed idempotent storage.
+//! Integrity module — manifest for content-addressed idempotent storage.
 //!
 //! # Idempotency Guarantee
 //! Every downloaded artifact is stored under a content-addressed filename:
-//! `<sha256_hex>.<ext>`.  Before writing, the manifest is consulted —
-//! if the hash already exists, the write is skipped.  This makes every
+//! `<digest_hex>.<ext>`.  Before writing, the manifest is consulted —
+//! if the digest already exists, the write is skipped.  This makes every
 //! download operation **idempotent**: running the tool twice on the same
 //! Substack produces the exact same output directory with zero wasted I/O.
+//!
+//! # Pluggable hash backend
+//! The digest algorithm (SHA-256 or BLAKE3, see [`HashAlgo`]) is recorded
+//! on every [`ManifestEntry`] rather than assumed, so a manifest can mix
+//! entries produced by different algorithms and still verify — each entry
+//! is checked with the algorithm that produced it.
+//!
+//! # SRI-style multi-algorithm digests
+//! [`ManifestEntry::digest`] accepts either a bare hex digest (verified
+//! with the entry's `algo`, for manifests predating this format) or an
+//! [SRI](https://www.w3.org/TR/SRI/)-style spec of one or more
+//! whitespace-separated `<algo>-<base64digest>` tokens covering `sha256`,
+//! `sha384`, and `sha512`. When a spec lists more than one, only the
+//! strongest-named algorithm present needs to match (the SRI "prioritized
+//! hash" rule) — see [`verify_file`].
+//!
+//! # Whole-archive integrity
+//! [`Manifest::merkle_root`] folds every entry into a single digest, and
+//! [`Manifest::save`] persists it alongside the entries. [`Manifest::verify_all`]
+//! re-checks every file and recomputes the root, so a consumer can confirm
+//! an entire archive with one comparison instead of walking every entry.
+//!
+//! # Signed manifests
+//! [`Manifest::save_signed`] and [`Manifest::load_verified`] add TUF-style
+//! detached ed25519 signatures over `manifest.json`'s exact serialized
+//! bytes, stored as a sibling `manifest.json.sig`. Verification fails
+//! closed: an invalid signature is a hard error, and a missing one only
+//! succeeds if the caller explicitly opts down to [`Manifest::load_or_create`]
+//! instead.
+//!
+//! # Portable archives
+//! [`export_tar`] streams a self-describing tar of the content-addressed
+//! store (manifest first, files after, each verified as it is appended),
+//! and [`import_tar`] reverses this: unpack, re-hash every file, and
+//! rebuild the manifest rather than trusting the embedded one.
+//!
+//! # Path containment without touching the filesystem
+//! Containment checks (`validate_path_is_safe`, `should_skip`,
+//! `verify_file`) resolve paths *logically* first (the path-dedot
+//! approach, see `absolutize`), so they work for paths that do not exist
+//! yet and are not sensitive to Windows' `\\?\` verbatim prefix. Real
+//! `std::fs::canonicalize` — which resolves symlinks — only runs once a
+//! path is confirmed to exist, since that is the only point a symlink
+//! swap matters.
+//!
+//! # Configurable sandbox
+//! [`Sandbox`] holds the allowed root director(ies) for the containment
+//! checks above, so a caller can permit e.g. `/mnt/archive` explicitly
+//! instead of being restricted to the process's current working
+//! directory. [`Sandbox::default`] is the single-root CWD sandbox, which
+//! preserves prior behaviour for callers that don't configure one.
 
-use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use base64::Engine as _;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha384, Sha512};
 use std::env;
 
+use crate::cli::HashAlgo;
+
 
 // ---------------------------------------------------------------------------
 // Manifest — idempotent download tracking
@@ -35,28 +89,129 @@ use std::env;
 /// Hardcoded manifest filename — never derived from user input.
 const MANIFEST_FILENAME: &str = "manifest.json";
 
+/// Detached-signature filename, always a sibling of [`MANIFEST_FILENAME`].
+const SIGNATURE_FILENAME: &str = "manifest.json.sig";
+
+/// Current on-disk schema version for `manifest.json`.
+///
+/// Bump this whenever `ManifestEntry`/`Manifest` gain a field that changes
+/// the meaning of existing data (new optional fields with `#[serde(default)]`
+/// do not require a bump).
+const MANIFEST_VERSION: u32 = 1;
+
+// ---------------------------------------------------------------------------
+// Sandbox — configurable allowed roots for path containment checks
+// ---------------------------------------------------------------------------
+
+/// One or more directories a path is permitted to resolve within.
+///
+/// Passed to [`validate_path_is_safe`], [`should_skip`], and [`verify_file`]
+/// (and everything built on them) so a caller can declare the permitted
+/// base(s) explicitly at startup, rather than always being restricted to
+/// the process's current working directory.
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    roots: Vec<PathBuf>,
+}
+
+impl Default for Sandbox {
+    /// A single-root sandbox permitting only the current working
+    /// directory, matching the behaviour of every caller before `Sandbox`
+    /// existed.
+    fn default() -> Self {
+        Self { roots: vec![PathBuf::from(".")] }
+    }
+}
+
+impl Sandbox {
+    /// Build a sandbox permitting paths under any of `roots`.
+    #[must_use]
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self { roots }
+    }
+}
+
 /// Entry in the download manifest tracking one artifact.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ManifestEntry {
     /// Original source URL.
     pub source_url: String,
-    /// SHA-256 hex digest of the content.
-    pub sha256: String,
+    /// Either a bare hex digest (in the algorithm recorded by `algo`, for
+    /// manifests predating the SRI format) or an SRI-style spec of one or
+    /// more whitespace-separated `<algo>-<base64digest>` tokens — see
+    /// [`verify_file`].
+    #[serde(alias = "sha256")]
+    pub digest: String,
     /// Relative path within the output directory.
     pub local_path: String,
     /// Content length in bytes.
     pub size: u64,
     /// ISO-8601 timestamp of when this entry was recorded.
     pub downloaded_at: String,
+    /// Slug of the originating post, if this entry is a converted post body
+    /// rather than a downloaded asset (image/attachment).
+    #[serde(default)]
+    pub slug: Option<String>,
+    /// Publish date of the originating post (as reported by the Substack
+    /// API), if known.
+    #[serde(default)]
+    pub post_date: Option<String>,
+    /// Hash algorithm that produced `digest`. Defaults to SHA-256 so
+    /// manifests written before this field existed keep parsing and
+    /// verifying correctly.
+    #[serde(default)]
+    pub algo: HashAlgo,
+    /// Size in bytes of the file actually written to `local_path`, if
+    /// compression (`--compress`) is active. `None` when the file on disk
+    /// is stored raw, in which case it equals `size`. `digest`/`size`
+    /// always describe the *uncompressed* content so idempotency checks
+    /// are unaffected by toggling compression between runs.
+    #[serde(default)]
+    pub compressed_size: Option<u64>,
+    /// Content encoding applied to `local_path` on disk (`"gzip"`, `"br"`,
+    /// or `"zstd"`), or `None` if stored raw.
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Subresource-integrity record — `sha256`/`sha384` tokens in the same
+    /// whitespace-separated `<algo>-<base64digest>` format [`verify_file`]
+    /// already parses, computed alongside the primary `digest`/`algo` pair
+    /// for assets downloaded by `handlers::download`. `None` for entries
+    /// predating this field.
+    #[serde(default)]
+    pub sri: Option<String>,
 }
 
 /// Download manifest tracking all artifacts for idempotent re-runs.
 ///
-/// Persisted as `manifest.json` in the output directory.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Persisted as `manifest.json` in the output directory. `entries` is a
+/// flat `Vec` (rather than a map) so the on-disk JSON is a stable, ordered
+/// list that reads naturally and diffs cleanly between runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Manifest {
-    /// Map from SHA-256 hex digest to manifest entry.
-    entries: HashMap<String, ManifestEntry>,
+    /// Schema version of this manifest file.
+    pub version: u32,
+    /// RFC 3339 timestamp of when this manifest was last written.
+    #[serde(default)]
+    pub generated_at: String,
+    /// All tracked artifacts.
+    #[serde(default)]
+    pub entries: Vec<ManifestEntry>,
+    /// Merkle root over all entries, as computed by [`Manifest::merkle_root`]
+    /// at the time this manifest was last saved. `None` for manifests
+    /// written before this field existed, or for an empty manifest.
+    #[serde(default)]
+    pub merkle_root: Option<String>,
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            generated_at: String::new(),
+            entries: Vec::new(),
+            merkle_root: None,
+        }
+    }
 }
 
 impl Manifest {
@@ -64,7 +219,7 @@ impl Manifest {
     ///
     /// # Errors
     /// Returns `anyhow::Error` if the file exists but cannot be parsed.
-    pub fn load_or_create(output_dir: &Path) -> anyhow::Result<Self> {
+    pub fn load_or_create(output_dir: &Path, sandbox: &Sandbox) -> anyhow::Result<Self> {
         use std::io::Read;
 
         // Defence-in-depth: canonicalize output_dir to resolve symlinks and
@@ -72,10 +227,10 @@ impl Manifest {
         // We propagate the error instead of falling back to the raw path,
         // which would defeat path-traversal protection.
         //
-        // SECURITY FIX: We now also validate that the path is within the Current
-        // Working Directory (CWD) to prevent arbitrary file system access if
-        // `output_dir` is user-controlled.
-        let canonical_dir = validate_path_is_safe(output_dir)?;
+        // SECURITY FIX: We now also validate that the path is within an
+        // allowed sandbox root (the CWD by default) to prevent arbitrary
+        // file system access if `output_dir` is user-controlled.
+        let canonical_dir = validate_path_is_safe(output_dir, sandbox)?;
         let path = canonical_dir.join(MANIFEST_FILENAME);
 
         // Invariant: the filename component must be exactly the constant we
@@ -118,16 +273,106 @@ impl Manifest {
         // the canonical path validated above.
         let mut content = String::new();
         file.read_to_string(&mut content)?;
-        let manifest: Self = serde_json::from_str(&content)?;
-        Ok(manifest)
+        parse_manifest(&content)
     }
 
     /// Persist the manifest to disk as pretty-printed JSON.
     ///
     /// # Errors
     /// Returns `anyhow::Error` on I/O failure.
-    pub fn save(&self, output_dir: &Path) -> anyhow::Result<()> {
-        let canonical_dir = validate_path_is_safe(output_dir)?;
+    pub fn save(&mut self, output_dir: &Path, sandbox: &Sandbox) -> anyhow::Result<()> {
+        self.write_manifest_file(output_dir, sandbox)?;
+        Ok(())
+    }
+
+    /// Persist the manifest and a detached ed25519 signature over its exact
+    /// serialized bytes, written as `manifest.json.sig` next to
+    /// `manifest.json`.
+    ///
+    /// Signing happens over the bytes [`Manifest::save`] actually writes
+    /// (rather than re-serializing afterward) so the signature can never
+    /// drift from the file it covers.
+    ///
+    /// # Errors
+    /// Returns `anyhow::Error` on I/O failure.
+    pub fn save_signed(
+        &mut self,
+        output_dir: &Path,
+        signing_key: &SigningKey,
+        sandbox: &Sandbox,
+    ) -> anyhow::Result<()> {
+        let (canonical_dir, bytes) = self.write_manifest_file(output_dir, sandbox)?;
+        let signature = signing_key.sign(&bytes);
+        let sig_path = canonical_dir.join(SIGNATURE_FILENAME);
+        std::fs::write(&sig_path, signature.to_bytes())
+            .map_err(|e| anyhow::anyhow!("Cannot write {}: {e}", sig_path.display()))?;
+        Ok(())
+    }
+
+    /// Load `manifest.json`, requiring and verifying a detached
+    /// `manifest.json.sig` against `public_key`. Fails closed: a missing
+    /// signature is a hard error here — callers that want to accept
+    /// unsigned manifests must explicitly opt down to
+    /// [`Manifest::load_or_create`] instead.
+    ///
+    /// # Errors
+    /// Returns `anyhow::Error` if the manifest is missing or unparsable,
+    /// the signature file is missing or malformed, or the signature does
+    /// not verify against `public_key`.
+    pub fn load_verified(
+        output_dir: &Path,
+        public_key: &VerifyingKey,
+        sandbox: &Sandbox,
+    ) -> anyhow::Result<Self> {
+        let canonical_dir = validate_path_is_safe(output_dir, sandbox)?;
+        let manifest_path = canonical_dir.join(MANIFEST_FILENAME);
+        let sig_path = canonical_dir.join(SIGNATURE_FILENAME);
+
+        let bytes = std::fs::read(&manifest_path)
+            .map_err(|e| anyhow::anyhow!("Cannot read {}: {e}", manifest_path.display()))?;
+
+        if !sig_path.exists() {
+            anyhow::bail!(
+                "Missing detached signature {} — refusing to load an unverified manifest \
+                 (use Manifest::load_or_create to accept unsigned manifests)",
+                sig_path.display()
+            );
+        }
+
+        let sig_bytes = std::fs::read(&sig_path)
+            .map_err(|e| anyhow::anyhow!("Cannot read {}: {e}", sig_path.display()))?;
+        let sig_array: [u8; 64] = sig_bytes.as_slice().try_into().map_err(|_| {
+            anyhow::anyhow!(
+                "Malformed signature {} (expected 64 bytes, got {})",
+                sig_path.display(),
+                sig_bytes.len()
+            )
+        })?;
+        let signature = Signature::from_bytes(&sig_array);
+
+        public_key.verify(&bytes, &signature).map_err(|e| {
+            anyhow::anyhow!(
+                "Signature verification failed for {}: {e}",
+                manifest_path.display()
+            )
+        })?;
+
+        let content = String::from_utf8(bytes).map_err(|e| {
+            anyhow::anyhow!("Manifest {} is not valid UTF-8: {e}", manifest_path.display())
+        })?;
+        parse_manifest(&content)
+    }
+
+    /// Stamp version/timestamp/merkle root, serialize, and write
+    /// `manifest.json` to `output_dir`. Returns the canonical output
+    /// directory and the exact bytes written, so [`Manifest::save_signed`]
+    /// can sign precisely what landed on disk.
+    fn write_manifest_file(
+        &mut self,
+        output_dir: &Path,
+        sandbox: &Sandbox,
+    ) -> anyhow::Result<(PathBuf, Vec<u8>)> {
+        let canonical_dir = validate_path_is_safe(output_dir, sandbox)?;
         let path = canonical_dir.join(MANIFEST_FILENAME);
 
         // Invariant: same filename-component check as load_or_create.
@@ -149,20 +394,30 @@ impl Manifest {
             }
         }
 
+        self.version = MANIFEST_VERSION;
+        self.generated_at = chrono::Utc::now().to_rfc3339();
+        self.merkle_root = self.merkle_root();
         let json = serde_json::to_string_pretty(self)?;
-        std::fs::write(&path, json)?;
-        Ok(())
+        std::fs::write(&path, &json)?;
+        Ok((canonical_dir, json.into_bytes()))
     }
 
-    /// Check if content with the given SHA-256 hash has already been downloaded.
+    /// Check if content with the given digest has already been downloaded.
     #[must_use]
-    pub fn contains(&self, sha256: &str) -> bool {
-        self.entries.contains_key(sha256)
+    pub fn contains(&self, digest: &str) -> bool {
+        self.entries.iter().any(|e| e.digest == digest)
     }
 
     /// Record a new download in the manifest.
+    ///
+    /// If an entry with the same digest already exists it is replaced,
+    /// keeping the manifest free of duplicate content-addressed entries.
     pub fn insert(&mut self, entry: ManifestEntry) {
-        self.entries.insert(entry.sha256.clone(), entry);
+        if let Some(existing) = self.entries.iter_mut().find(|e| e.digest == entry.digest) {
+            *existing = entry;
+        } else {
+            self.entries.push(entry);
+        }
     }
 
     /// Returns the number of entries in the manifest.
@@ -179,7 +434,116 @@ impl Manifest {
 
     /// Returns an iterator over all entries.
     pub fn entries(&self) -> impl Iterator<Item = &ManifestEntry> {
-        self.entries.values()
+        self.entries.iter()
+    }
+
+    /// Compute a deterministic Merkle root over all entries.
+    ///
+    /// Entries are sorted by `local_path` first so the result does not
+    /// depend on insertion order. Each leaf is `H(local_path || 0x00 ||
+    /// digest)`, and interior nodes are `H(left || right)`, duplicating the
+    /// last node at any level with an odd number of nodes. Returns `None`
+    /// for an empty manifest — there is no tree to root.
+    #[must_use]
+    pub fn merkle_root(&self) -> Option<String> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<&ManifestEntry> = self.entries.iter().collect();
+        sorted.sort_by(|a, b| a.local_path.cmp(&b.local_path));
+
+        let mut level: Vec<[u8; 32]> = sorted.into_iter().map(merkle_leaf).collect();
+        while level.len() > 1 {
+            let mut next = Vec::with_capacity(level.len().div_ceil(2));
+            for pair in level.chunks(2) {
+                let right = pair.get(1).unwrap_or(&pair[0]);
+                next.push(merkle_combine(&pair[0], right));
+            }
+            level = next;
+        }
+
+        Some(hex::encode(level[0]))
+    }
+
+    /// Re-hash every entry's file on disk and confirm the archive matches
+    /// this manifest: every file must exist, verify against its recorded
+    /// digest (using its own `algo`), and the recomputed Merkle root must
+    /// equal the root persisted on this manifest. Detects both missing
+    /// files and silent corruption with a single boolean.
+    ///
+    /// # Errors
+    /// Returns `anyhow::Error` if `base_dir` itself cannot be resolved
+    /// (e.g. it does not exist).
+    pub fn verify_all(&self, base_dir: &Path, sandbox: &Sandbox) -> anyhow::Result<bool> {
+        // Resolve base_dir eagerly so a bad base directory is reported as an
+        // error rather than silently surfacing as "every file missing".
+        validate_path_is_safe(base_dir, sandbox)?;
+
+        for entry in &self.entries {
+            match verify_file(base_dir, &entry.local_path, &entry.digest, entry.algo, sandbox) {
+                Ok((true, _)) => {}
+                Ok((false, _)) | Err(_) => return Ok(false),
+            }
+        }
+
+        Ok(self.merkle_root() == self.merkle_root)
+    }
+}
+
+/// Hash one manifest entry's Merkle leaf: `H(local_path || 0x00 || digest)`.
+///
+/// The tree itself always uses SHA-256 regardless of the per-entry `algo`,
+/// so a manifest mixing SHA-256 and BLAKE3 content digests still roots to
+/// a single well-defined value.
+fn merkle_leaf(entry: &ManifestEntry) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(entry.local_path.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(entry.digest.as_bytes());
+    hasher.finalize().into()
+}
+
+/// Combine two Merkle nodes: `H(left || right)`.
+fn merkle_combine(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Parse a `manifest.json` document.
+///
+/// This is the single entry point for turning untrusted bytes into a
+/// [`Manifest`] — used both by [`Manifest::load_or_create`] and directly by
+/// the `parse_manifest` fuzz target. Malformed, truncated, or
+/// unexpectedly-shaped JSON always returns `Err` rather than panicking.
+///
+/// # Errors
+/// Returns `anyhow::Error` if `data` is not valid JSON or does not match
+/// the `Manifest` schema.
+pub fn parse_manifest(data: &str) -> anyhow::Result<Manifest> {
+    serde_json::from_str(data).map_err(|e| anyhow::anyhow!("Failed to parse manifest.json: {e}"))
+}
+
+/// Outcome of re-verifying a single manifest entry against disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditStatus {
+    /// File exists and its digest matches the manifest.
+    Ok,
+    /// File is absent from disk.
+    Missing,
+    /// File exists but its digest no longer matches the manifest.
+    Modified,
+}
+
+impl std::fmt::Display for AuditStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ok => write!(f, "OK"),
+            Self::Missing => write!(f, "MISSING"),
+            Self::Modified => write!(f, "MODIFIED"),
+        }
     }
 }
 
@@ -187,37 +551,125 @@ impl Manifest {
 // Path sanitisation — defence-in-depth against path traversal
 // ---------------------------------------------------------------------------
 
-/// Validate that a path resides within the Current Working Directory (CWD).
+/// Logically resolve `path` to an absolute path without touching the
+/// filesystem (the path-dedot approach): join it onto the current
+/// directory if relative, then fold `Component`s in a stack — popping on
+/// `ParentDir`, skipping `CurDir`, and pushing everything else.
+///
+/// Unlike `std::fs::canonicalize`, this works for paths that do not exist
+/// yet and never resolves symlinks, so it is safe to use for containment
+/// checks before a file or directory has been created.
+///
+/// # Errors
+/// Returns `anyhow::Error` if `path` is relative and the current working
+/// directory cannot be determined.
+fn absolutize(path: &Path) -> anyhow::Result<PathBuf> {
+    let joined = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        let cwd = env::current_dir()
+            .map_err(|e| anyhow::anyhow!("Cannot determine current working directory: {e}"))?;
+        cwd.join(path)
+    };
+
+    let mut stack: Vec<std::path::Component> = Vec::new();
+    for component in joined.components() {
+        match component {
+            std::path::Component::ParentDir => {
+                stack.pop();
+            }
+            std::path::Component::CurDir => {}
+            other => stack.push(other),
+        }
+    }
+
+    Ok(stack.into_iter().collect())
+}
+
+/// Strip Windows' `\\?\` verbatim-path prefix (dunce-style) so that
+/// `starts_with` comparisons against a plainly-formed path remain reliable.
+/// A no-op everywhere else.
+fn strip_verbatim_prefix(path: &Path) -> PathBuf {
+    match path.to_str() {
+        Some(s) => s.strip_prefix(r"\\?\").map_or_else(|| path.to_path_buf(), PathBuf::from),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Validate that a path resides within one of `sandbox`'s allowed roots
+/// (the CWD alone, by default).
 ///
 /// This prevents path traversal attacks where a user provides a path like
-/// `../../etc/passwd` or `/tmp/malicious`.
+/// `../../etc/passwd` or `/tmp/malicious`. The containment check is done
+/// lexically via [`absolutize`] so it works even before `path` exists.
+/// Real canonicalization (which resolves symlinks) only happens — and is
+/// only needed — once `path` actually exists on disk, since that is the
+/// only point a symlink could have been swapped in.
 ///
 /// # Errors
 /// Returns `anyhow::Error` if:
-/// - The path cannot be canonicalized.
-/// - The CWD cannot be determined.
-/// - The path is not within the CWD.
-fn validate_path_is_safe(path: &Path) -> anyhow::Result<PathBuf> {
-    let canonical_path = std::fs::canonicalize(path)
-        .map_err(|e| anyhow::anyhow!("Cannot resolve path {}: {e}", path.display()))?;
-
-    let cwd = env::current_dir()
-        .map_err(|e| anyhow::anyhow!("Cannot determine current working directory: {e}"))?;
+/// - A relative sandbox root or `path` cannot be resolved against the CWD.
+/// - The path (logically, or after resolving symlinks if it exists) is not
+///   within any of `sandbox`'s roots.
+/// - The path exists but its symlinks cannot be resolved.
+fn validate_path_is_safe(path: &Path, sandbox: &Sandbox) -> anyhow::Result<PathBuf> {
+    let logical_path = absolutize(path)?;
+
+    let logical_roots = sandbox
+        .roots
+        .iter()
+        .map(|root| absolutize(root))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if !logical_roots.iter().any(|root| logical_path.starts_with(root)) {
+        anyhow::bail!(
+            "Path traversal blocked: Path {} is outside the permitted sandbox root(s): {}",
+            logical_path.display(),
+            format_roots(&logical_roots)
+        );
+    }
 
-    let canonical_cwd = std::fs::canonicalize(&cwd)
-        .map_err(|e| anyhow::anyhow!("Cannot resolve CWD {}: {e}", cwd.display()))?;
+    if !logical_path.exists() {
+        // Nothing to resolve yet — the lexical check above is all we can
+        // (and need to) do.
+        return Ok(logical_path);
+    }
 
-    if !canonical_path.starts_with(&canonical_cwd) {
+    let canonical_path = std::fs::canonicalize(&logical_path)
+        .map(|p| strip_verbatim_prefix(&p))
+        .map_err(|e| anyhow::anyhow!("Cannot resolve path {}: {e}", logical_path.display()))?;
+
+    // Canonicalize each root too, so a symlinked root resolves to the same
+    // place as a symlinked path. A root that doesn't exist yet (or can't be
+    // canonicalized for some other reason) falls back to its logical form —
+    // it was already checked above, and there's nothing further to resolve.
+    let canonical_roots: Vec<PathBuf> = sandbox
+        .roots
+        .iter()
+        .zip(&logical_roots)
+        .map(|(root, logical_root)| {
+            std::fs::canonicalize(root)
+                .map(|p| strip_verbatim_prefix(&p))
+                .unwrap_or_else(|_| logical_root.clone())
+        })
+        .collect();
+
+    if !canonical_roots.iter().any(|root| canonical_path.starts_with(root)) {
         anyhow::bail!(
-            "Path traversal blocked: Path {} is outside the current working directory {}",
+            "Path traversal blocked: Path {} is outside the permitted sandbox root(s): {}",
             canonical_path.display(),
-            canonical_cwd.display()
+            format_roots(&canonical_roots)
         );
     }
 
     Ok(canonical_path)
 }
 
+/// Render a list of sandbox roots for an error message.
+fn format_roots(roots: &[PathBuf]) -> String {
+    roots.iter().map(|r| r.display().to_string()).collect::<Vec<_>>().join(", ")
+}
+
 
 /// Strip directory-traversal components from an untrusted filename.
 ///
@@ -256,45 +708,168 @@ pub fn sanitize_filename(name: &str) -> String {
 // Content-addressed helpers
 // ---------------------------------------------------------------------------
 
+/// Compute the hex digest of a byte slice using `algo`.
+#[must_use]
+pub fn digest_hex(algo: HashAlgo, data: &[u8]) -> String {
+    match algo {
+        HashAlgo::Sha256 => {
+            let mut hasher = Sha256::new();
+            hasher.update(data);
+            hex::encode(hasher.finalize())
+        }
+        HashAlgo::Blake3 => blake3::hash(data).to_hex().to_string(),
+    }
+}
+
 /// Compute the SHA-256 hex digest of a byte slice.
 #[must_use]
 pub fn sha256_hex(data: &[u8]) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(data);
-    hex::encode(hasher.finalize())
+    digest_hex(HashAlgo::Sha256, data)
+}
+
+/// Compute the hex digest of a file on disk using `algo`.
+///
+/// For [`HashAlgo::Blake3`], hashing goes through rayon-backed tree hashing
+/// over a memory-mapped view of the file (`Hasher::update_mmap_rayon`), so
+/// multi-megabyte attachments (podcasts, video) hash across cores instead
+/// of single-threaded. SHA-256 falls back to reading the whole file into
+/// memory, matching the existing buffered-hashing behaviour.
+///
+/// # Errors
+/// Returns `anyhow::Error` if the file cannot be opened or read.
+pub fn digest_file(algo: HashAlgo, path: &Path) -> anyhow::Result<String> {
+    match algo {
+        HashAlgo::Sha256 => {
+            let data = std::fs::read(path)
+                .map_err(|e| anyhow::anyhow!("Cannot read {}: {e}", path.display()))?;
+            Ok(digest_hex(HashAlgo::Sha256, &data))
+        }
+        HashAlgo::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            hasher
+                .update_mmap_rayon(path)
+                .map_err(|e| anyhow::anyhow!("Cannot hash {}: {e}", path.display()))?;
+            Ok(hasher.finalize().to_hex().to_string())
+        }
+    }
+}
+
+/// Compute a combined SRI record for `data`: `"sha256-<base64> sha384-<base64>"`.
+///
+/// Stored in [`ManifestEntry::sri`] alongside the primary `digest`/`algo`
+/// pair so `audit --verify` can catch a collision on the primary algorithm
+/// alone — [`verify_file`] already parses this exact format.
+#[must_use]
+pub fn compute_sri(data: &[u8]) -> String {
+    let sha256 = base64::engine::general_purpose::STANDARD.encode(Sha256::digest(data));
+    let sha384 = base64::engine::general_purpose::STANDARD.encode(Sha384::digest(data));
+    format!("sha256-{sha256} sha384-{sha384}")
+}
+
+// ---------------------------------------------------------------------------
+// Signing key material — for `--sign-key`/`--verify-key`
+// ---------------------------------------------------------------------------
+
+/// Load an ed25519 signing (secret) key from `path`, for `download --sign-key`.
+///
+/// Accepts either the raw 32 secret-key bytes, or the same bytes encoded as
+/// 64 hex characters (trailing whitespace tolerated) — whichever is more
+/// convenient for the caller to generate and store.
+///
+/// # Errors
+/// Returns `anyhow::Error` if the file cannot be read or is neither 32 raw
+/// bytes nor valid 64-character hex.
+pub fn load_signing_key(path: &Path) -> anyhow::Result<SigningKey> {
+    read_key_bytes(path).map(|bytes| SigningKey::from_bytes(&bytes))
+}
+
+/// Load an ed25519 verifying (public) key from `path`, for `audit --verify-key`.
+///
+/// Accepts the same raw-bytes-or-hex encodings as [`load_signing_key`].
+///
+/// # Errors
+/// Returns `anyhow::Error` if the file cannot be read, is neither 32 raw
+/// bytes nor valid 64-character hex, or the bytes are not a valid
+/// compressed ed25519 point.
+pub fn load_verifying_key(path: &Path) -> anyhow::Result<VerifyingKey> {
+    let bytes = read_key_bytes(path)?;
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| anyhow::anyhow!("Invalid ed25519 public key in {}: {e}", path.display()))
+}
+
+/// Read `path` and interpret it as 32 raw key bytes, or 64 hex characters
+/// decoding to the same.
+fn read_key_bytes(path: &Path) -> anyhow::Result<[u8; 32]> {
+    let raw = std::fs::read(path)
+        .map_err(|e| anyhow::anyhow!("Cannot read key file {}: {e}", path.display()))?;
+
+    if let Ok(array) = <[u8; 32]>::try_from(raw.as_slice()) {
+        return Ok(array);
+    }
+
+    let text = std::str::from_utf8(&raw)
+        .map_err(|_| anyhow::anyhow!("Key file {} is neither 32 raw bytes nor hex text", path.display()))?
+        .trim();
+    let decoded = hex::decode(text)
+        .map_err(|e| anyhow::anyhow!("Key file {} is not valid hex: {e}", path.display()))?;
+    <[u8; 32]>::try_from(decoded.as_slice()).map_err(|_| {
+        anyhow::anyhow!(
+            "Key file {} decoded to {} bytes, expected 32",
+            path.display(),
+            decoded.len()
+        )
+    })
 }
 
 /// Determine whether a download should be skipped (idempotency check).
 ///
-/// Returns `true` if the content hash already exists in the manifest
+/// Returns `true` if the content digest already exists in the manifest
 /// **and** the corresponding file exists on disk.
 ///
 /// `local_path` is sanitised before joining to ensure it cannot escape
-/// `output_dir` via directory-traversal sequences.  `output_dir` is
-/// canonicalized to resolve symlinks and traversal sequences before the
-/// join, and the resulting path is verified to remain inside the
-/// canonical directory.
+/// `output_dir` via directory-traversal sequences. `output_dir` is
+/// logically absolutized (not canonicalized) for the containment check, so
+/// a not-yet-created `output_dir` is handled the same as one that already
+/// exists — both simply resolve to "no, don't skip" once we reach the
+/// `exists()` check. Real canonicalization only runs once the target is
+/// confirmed to exist, to rule out a symlink swap. `output_dir` must also
+/// fall within one of `sandbox`'s allowed roots; outside of it, this
+/// conservatively returns `false` rather than skipping the download.
 #[must_use]
-pub fn should_skip(manifest: &Manifest, sha256: &str, output_dir: &Path, local_path: &str) -> bool {
-    if !manifest.contains(sha256) {
+pub fn should_skip(
+    manifest: &Manifest,
+    digest: &str,
+    output_dir: &Path,
+    local_path: &str,
+    sandbox: &Sandbox,
+) -> bool {
+    if !manifest.contains(digest) {
         return false;
     }
     let safe_name = sanitize_filename(local_path);
-    // Canonicalize the base directory; if it cannot be resolved the file
-    // cannot exist, so we conservatively return false (do not skip).
-    let canonical_dir = match std::fs::canonicalize(output_dir) {
-        Ok(d) => d,
-        Err(_) => return false,
+
+    let Ok(logical_dir) = absolutize(output_dir) else {
+        return false;
     };
-    let target = canonical_dir.join(&safe_name);
-    // Verify the resolved target is still inside the canonical directory.
+    let Ok(logical_roots) = sandbox.roots.iter().map(|root| absolutize(root)).collect::<anyhow::Result<Vec<_>>>() else {
+        return false;
+    };
+    if !logical_roots.iter().any(|root| logical_dir.starts_with(root)) {
+        return false;
+    }
+    let target = logical_dir.join(&safe_name);
+
+    if !target.starts_with(&logical_dir) || !target.exists() {
+        return false;
+    }
+
     match std::fs::canonicalize(&target) {
-        Ok(canonical_target) => canonical_target.starts_with(&canonical_dir),
-        Err(_) => false, // File does not exist or cannot be resolved.
+        Ok(canonical_target) => strip_verbatim_prefix(&canonical_target).starts_with(&logical_dir),
+        Err(_) => false, // File vanished or cannot be resolved between the exists() check and here.
     }
 }
 
-/// Build a content-addressed filename: `<sha256_prefix>_<original_name>`.
+/// Build a content-addressed filename: `<digest_prefix>_<original_name>`.
 ///
 /// Uses only the first 16 hex chars of the digest to keep filenames readable
 /// while still preventing collisions in practice.
@@ -302,31 +877,152 @@ pub fn should_skip(manifest: &Manifest, sha256: &str, output_dir: &Path, local_p
 /// `original_filename` is sanitised to strip traversal sequences so that
 /// the returned path is always a plain filename with no directory component.
 #[must_use]
-pub fn content_addressed_path(sha256: &str, original_filename: &str) -> PathBuf {
+pub fn content_addressed_path(digest: &str, original_filename: &str) -> PathBuf {
     let safe_name = sanitize_filename(original_filename);
-    let prefix = &sha256[..16.min(sha256.len())];
+    let prefix = &digest[..16.min(digest.len())];
     PathBuf::from(format!("{prefix}_{safe_name}"))
 }
 
-/// Verify a single file against its expected SHA-256 hash.
+// ---------------------------------------------------------------------------
+// SRI-style multi-algorithm digests
+// ---------------------------------------------------------------------------
+
+/// Digest algorithms usable in an SRI-style [`ManifestEntry::digest`] spec,
+/// ordered weakest-to-strongest so the "prioritized hash" rule (only the
+/// strongest-named algorithm present must match) can be implemented with a
+/// plain `max_by_key`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SriAlgo {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl SriAlgo {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    fn parse_label(label: &str) -> Option<Self> {
+        match label {
+            "sha256" => Some(Self::Sha256),
+            "sha384" => Some(Self::Sha384),
+            "sha512" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    fn digest(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Sha256 => Sha256::digest(data).to_vec(),
+            Self::Sha384 => Sha384::digest(data).to_vec(),
+            Self::Sha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+}
+
+/// One `<algo>-<base64digest>` token parsed out of an SRI-style digest spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SriToken {
+    algo: SriAlgo,
+    expected: Vec<u8>,
+}
+
+/// Returns `true` if `spec` looks like an SRI-style digest spec (its first
+/// whitespace-separated token contains a `-`) rather than a bare hex digest.
+fn looks_like_sri_spec(spec: &str) -> bool {
+    spec.split_whitespace().next().is_some_and(|first| first.contains('-'))
+}
+
+/// Parse a space-separated SRI digest spec (e.g. `sha256-... sha384-...`)
+/// into its component tokens.
+///
+/// # Errors
+/// Returns `anyhow::Error` if any token is missing its `-` separator, names
+/// an algorithm other than `sha256`/`sha384`/`sha512`, or fails to
+/// base64-decode — verification fails closed rather than silently skipping
+/// an unparseable digest.
+fn parse_sri_spec(spec: &str) -> anyhow::Result<Vec<SriToken>> {
+    spec.split_whitespace()
+        .map(|token| {
+            let (label, encoded) = token.split_once('-').ok_or_else(|| {
+                anyhow::anyhow!("Malformed digest spec token {token:?}: missing algorithm prefix")
+            })?;
+            let algo = SriAlgo::parse_label(label).ok_or_else(|| {
+                anyhow::anyhow!("Unrecognized digest algorithm {label:?} in {token:?}")
+            })?;
+            let expected = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| anyhow::anyhow!("Malformed base64 digest in {token:?}: {e}"))?;
+            Ok(SriToken { algo, expected })
+        })
+        .collect()
+}
+
+/// Verify a single file against its expected digest, returning
+/// `(passed, algo_label)` so callers can report which algorithm was
+/// actually used.
 ///
 /// The `relative_path` is sanitised before being joined to `base_dir` so
 /// that path-traversal sequences like `../../etc/shadow` are neutralised.
 /// Additionally, the canonical (resolved) path is checked to ensure it
 /// still resides inside `base_dir`, providing defence-in-depth.
 ///
+/// `expected_digest` is either an SRI-style spec (see
+/// [`parse_sri_spec`]), verified against its strongest-named algorithm
+/// regardless of `algo`, or a bare hex digest verified with `algo` — the
+/// entry's recorded algorithm, for manifests predating the SRI format.
+///
 /// # Errors
 /// Returns `anyhow::Error` if:
+///   * `base_dir` is outside `sandbox`'s allowed roots,
 ///   * the resolved path escapes `base_dir`,
-///   * the file cannot be read, or
-///   * the hash does not match.
+///   * the file cannot be read,
+///   * `expected_digest` is an SRI spec with a malformed or unrecognized
+///     token, or
+///   * the digest does not match.
 pub fn verify_file(
     base_dir: &Path,
     relative_path: &str,
-    expected_sha256: &str,
-) -> anyhow::Result<bool> {
-    use std::io::Read;
+    expected_digest: &str,
+    algo: HashAlgo,
+    sandbox: &Sandbox,
+) -> anyhow::Result<(bool, String)> {
+    let canonical_file = resolve_verified_path(base_dir, relative_path, sandbox)?;
+
+    // Digest the *resolved* path — `canonical_file` was validated above to
+    // still reside inside the base directory, closing the TOCTOU gap between
+    // path construction and the read/mmap that `digest_file` performs.
+    if looks_like_sri_spec(expected_digest) {
+        let tokens = parse_sri_spec(expected_digest)?;
+        let strongest = tokens
+            .iter()
+            .max_by_key(|t| t.algo)
+            .ok_or_else(|| anyhow::anyhow!("Empty digest spec"))?;
+        let data = std::fs::read(&canonical_file)
+            .map_err(|e| anyhow::anyhow!("Cannot read {}: {e}", canonical_file.display()))?;
+        let actual = strongest.algo.digest(&data);
+        return Ok((actual == strongest.expected, strongest.algo.label().to_owned()));
+    }
+
+    let actual = digest_file(algo, &canonical_file)?;
+    Ok((actual == expected_digest, algo.to_string()))
+}
 
+/// Resolve `relative_path` against `base_dir`, applying the same
+/// defence-in-depth checks as [`verify_file`] (empty/traversal rejection,
+/// filename sanitisation, sandbox containment, and symlink resolution),
+/// returning the canonical on-disk path once confirmed to exist and to
+/// reside inside `base_dir`. Shared by [`verify_file`] and
+/// [`verify_file_compressed`] so both apply identical path-safety logic.
+///
+/// # Errors
+/// Returns `anyhow::Error` under the same conditions as [`verify_file`].
+fn resolve_verified_path(base_dir: &Path, relative_path: &str, sandbox: &Sandbox) -> anyhow::Result<PathBuf> {
     // Defence layer 1: reject obviously malicious input before any path operations.
     // This explicit check makes the security boundary visible to static analysers.
     let trimmed = relative_path.trim();
@@ -343,18 +1039,20 @@ pub fn verify_file(
     let safe_name = sanitize_filename(relative_path);
 
     // Defence layer 3: canonicalise the base directory to an absolute, symlink-free path.
-    // SECURITY FIX: validation against CWD.
-    let canonical_base = validate_path_is_safe(base_dir)?;
+    // SECURITY FIX: validation against the sandbox.
+    let canonical_base = validate_path_is_safe(base_dir, sandbox)?;
 
     // Build the target path from the *canonical* base so the result is already
     // rooted in a resolved directory.  `safe_name` is guaranteed to be a plain
     // filename (no separators, no `..`) so the join cannot escape canonical_base.
     let target_path = canonical_base.join(&safe_name);
 
-    // Defence layer 4: canonicalise/resolve symlinks *before* opening.
-    // This prevents TOCTOU attacks where we check the path, but then open a
-    // symlink that was swapped in.
+    // Defence layer 4: canonicalise/resolve symlinks *before* opening. This
+    // is the one place a real (non-logical) canonicalize is unavoidable —
+    // we are about to read the file, so it must already exist, and that is
+    // exactly the case where a symlink could have been swapped in.
     let canonical_file = std::fs::canonicalize(&target_path)
+        .map(|p| strip_verbatim_prefix(&p))
         .map_err(|e| anyhow::anyhow!("Cannot resolve path {}: {e}", target_path.display()))?;
 
     if !canonical_file.starts_with(&canonical_base) {
@@ -365,17 +1063,189 @@ pub fn verify_file(
         );
     }
 
-    // Open the *resolved* path.
-    let mut file = std::fs::File::open(&canonical_file)
-        .map_err(|e| anyhow::anyhow!("Cannot open {}: {e}", canonical_file.display()))?;
+    Ok(canonical_file)
+}
+
+/// Like [`verify_file`], but transparently decompresses the on-disk bytes
+/// with [`crate::compress::decompress_bytes`] before hashing when `encoding`
+/// names a compression scheme (the value recorded on
+/// [`ManifestEntry::encoding`]) — `digest`/`sri` always describe the
+/// *uncompressed* content, so a compressed asset must be restored before
+/// either can be checked. `encoding: None` (or an unset/"stored raw" entry)
+/// behaves exactly like [`verify_file`].
+///
+/// # Errors
+/// Returns `anyhow::Error` under the same conditions as [`verify_file`], or
+/// if the compressed stream is truncated or corrupt.
+pub async fn verify_file_compressed(
+    base_dir: &Path,
+    relative_path: &str,
+    expected_digest: &str,
+    algo: HashAlgo,
+    sandbox: &Sandbox,
+    encoding: Option<&str>,
+) -> anyhow::Result<(bool, String)> {
+    let Some(encoding) = encoding else {
+        return verify_file(base_dir, relative_path, expected_digest, algo, sandbox);
+    };
+
+    let canonical_file = resolve_verified_path(base_dir, relative_path, sandbox)?;
+    let raw = std::fs::read(&canonical_file)
+        .map_err(|e| anyhow::anyhow!("Cannot read {}: {e}", canonical_file.display()))?;
+    let data = crate::compress::decompress_bytes(encoding, &raw).await?;
+
+    if looks_like_sri_spec(expected_digest) {
+        let tokens = parse_sri_spec(expected_digest)?;
+        let strongest = tokens
+            .iter()
+            .max_by_key(|t| t.algo)
+            .ok_or_else(|| anyhow::anyhow!("Empty digest spec"))?;
+        let actual = strongest.algo.digest(&data);
+        return Ok((actual == strongest.expected, strongest.algo.label().to_owned()));
+    }
+
+    let actual = digest_hex(algo, &data);
+    Ok((actual == expected_digest, algo.to_string()))
+}
+
+// ---------------------------------------------------------------------------
+// Portable archive export/import — ship the content-addressed store as tar
+// ---------------------------------------------------------------------------
+
+/// Stream `manifest`'s tracked files into a tar archive written to
+/// `writer`, with `manifest.json` as the first member so the result is
+/// self-describing. Each file's digest is verified against its manifest
+/// entry immediately before being appended, so a corrupted store cannot
+/// silently produce a valid-looking archive.
+///
+/// Relative paths and sizes are preserved exactly from `manifest`; each
+/// header's mtime is derived from the entry's `downloaded_at` timestamp.
+///
+/// # Errors
+/// Returns `anyhow::Error` if a tracked file fails digest verification,
+/// cannot be read, or if writing to `writer` fails.
+pub fn export_tar<W: std::io::Write>(
+    manifest: &Manifest,
+    base_dir: &Path,
+    writer: W,
+    sandbox: &Sandbox,
+) -> anyhow::Result<()> {
+    let canonical_base = validate_path_is_safe(base_dir, sandbox)?;
+    let mut builder = tar::Builder::new(writer);
+
+    let manifest_json = serde_json::to_vec_pretty(manifest)?;
+    let mut manifest_header = tar::Header::new_gnu();
+    manifest_header.set_size(manifest_json.len() as u64);
+    manifest_header.set_mode(0o644);
+    manifest_header.set_mtime(parse_mtime(&manifest.generated_at));
+    manifest_header.set_cksum();
+    builder.append_data(&mut manifest_header, MANIFEST_FILENAME, manifest_json.as_slice())?;
+
+    for entry in manifest.entries() {
+        let (verified, _) =
+            verify_file(&canonical_base, &entry.local_path, &entry.digest, entry.algo, sandbox)?;
+        if !verified {
+            anyhow::bail!(
+                "Refusing to export: {} fails digest verification against the manifest",
+                entry.local_path
+            );
+        }
+
+        let safe_name = sanitize_filename(&entry.local_path);
+        let file_path = canonical_base.join(&safe_name);
+        let mut file = std::fs::File::open(&file_path)
+            .map_err(|e| anyhow::anyhow!("Cannot open {}: {e}", file_path.display()))?;
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(entry.size);
+        header.set_mode(0o644);
+        header.set_mtime(parse_mtime(&entry.downloaded_at));
+        header.set_cksum();
+        builder.append_data(&mut header, &entry.local_path, &mut file)?;
+    }
+
+    builder.finish()?;
+    Ok(())
+}
+
+/// Parse an RFC 3339 timestamp into a Unix epoch for a tar header's mtime,
+/// falling back to `0` for entries predating this field or malformed data.
+fn parse_mtime(rfc3339: &str) -> u64 {
+    chrono::DateTime::parse_from_rfc3339(rfc3339)
+        .map(|dt| dt.timestamp().max(0) as u64)
+        .unwrap_or(0)
+}
+
+/// Unpack a tar archive produced by [`export_tar`] into `output_dir`,
+/// re-hashing every extracted file rather than trusting the embedded
+/// `manifest.json`, and rebuilding a fresh [`Manifest`] from those digests.
+/// Metadata (`source_url`, `slug`, `post_date`) is recovered from the
+/// embedded manifest where a `local_path` matches, but never the digest.
+///
+/// Any member whose path contains `..` or is absolute is rejected outright.
+///
+/// # Errors
+/// Returns `anyhow::Error` if the archive is malformed, a member has an
+/// unsafe path, or a file cannot be written to `output_dir`.
+pub fn import_tar<R: std::io::Read>(
+    reader: R,
+    output_dir: &Path,
+    sandbox: &Sandbox,
+) -> anyhow::Result<Manifest> {
+    use std::io::Read as _;
+
+    let canonical_dir = validate_path_is_safe(output_dir, sandbox)?;
+    let mut archive = tar::Archive::new(reader);
+
+    let mut archived_meta: Option<Manifest> = None;
+    let mut manifest = Manifest::default();
+
+    for entry_result in archive.entries()? {
+        let mut entry = entry_result?;
+        let path = entry.path()?.into_owned();
+        let path_str = path.to_string_lossy().into_owned();
+
+        if path_str.contains("..") || path.is_absolute() {
+            anyhow::bail!("Refusing to import tar member with unsafe path: {path_str:?}");
+        }
+
+        if path_str == MANIFEST_FILENAME {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            archived_meta = parse_manifest(&content).ok();
+            continue;
+        }
 
+        let safe_name = sanitize_filename(&path_str);
+        let dest_path = canonical_dir.join(&safe_name);
 
-    // Read through the already-opened handle — the data comes from the same
-    // inode that was validated above.
-    let mut data = Vec::new();
-    file.read_to_end(&mut data)?;
-    let actual = sha256_hex(&data);
-    Ok(actual == expected_sha256)
+        let mut contents = Vec::new();
+        entry.read_to_end(&mut contents)?;
+        std::fs::write(&dest_path, &contents)
+            .map_err(|e| anyhow::anyhow!("Cannot write {}: {e}", dest_path.display()))?;
+
+        let algo = HashAlgo::default();
+        let digest = digest_hex(algo, &contents);
+        let original = archived_meta
+            .as_ref()
+            .and_then(|m| m.entries.iter().find(|e| e.local_path == path_str));
+
+        manifest.insert(ManifestEntry {
+            source_url: original.map_or_else(String::new, |e| e.source_url.clone()),
+            digest,
+            local_path: path_str,
+            size: contents.len() as u64,
+            downloaded_at: chrono::Utc::now().to_rfc3339(),
+            slug: original.and_then(|e| e.slug.clone()),
+            post_date: original.and_then(|e| e.post_date.clone()),
+            algo,
+            compressed_size: original.and_then(|e| e.compressed_size),
+            encoding: original.and_then(|e| e.encoding.clone()),
+            sri: Some(compute_sri(&contents)),
+        });
+    }
+
+    Ok(manifest)
 }
 
 // ---------------------------------------------------------------------------
@@ -416,10 +1286,16 @@ mod tests {
         let mut m = Manifest::default();
         let entry = ManifestEntry {
             source_url: "https://example.com/post".into(),
-            sha256: "abc123".into(),
+            digest: "abc123".into(),
             local_path: "posts/abc123_post.html".into(),
             size: 1024,
             downloaded_at: "2026-02-15T00:00:00Z".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
         };
         m.insert(entry);
         assert!(m.contains("abc123"));
@@ -432,10 +1308,16 @@ mod tests {
         let mut m = Manifest::default();
         m.insert(ManifestEntry {
             source_url: "https://x.com/a".into(),
-            sha256: "deadbeef".into(),
+            digest: "deadbeef".into(),
             local_path: "a.html".into(),
             size: 512,
             downloaded_at: "2026-01-01T00:00:00Z".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
         });
 
         let json = serde_json::to_string(&m).unwrap();
@@ -453,14 +1335,20 @@ mod tests {
         let mut m = Manifest::default();
         m.insert(ManifestEntry {
             source_url: "https://x.com/b".into(),
-            sha256: "cafebabe".into(),
+            digest: "cafebabe".into(),
             local_path: "b.html".into(),
             size: 256,
             downloaded_at: "2026-02-15T01:00:00Z".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
         });
 
-        m.save(&dir).expect("save should succeed");
-        let loaded = Manifest::load_or_create(&dir).expect("load should succeed");
+        m.save(&dir, &Sandbox::default()).expect("save should succeed");
+        let loaded = Manifest::load_or_create(&dir, &Sandbox::default()).expect("load should succeed");
         assert!(loaded.contains("cafebabe"));
 
         // Cleanup.
@@ -474,7 +1362,8 @@ mod tests {
             &m,
             "abc",
             Path::new("/tmp"),
-            "nonexistent.html"
+            "nonexistent.html",
+            &Sandbox::default()
         ));
     }
 
@@ -497,8 +1386,10 @@ mod tests {
             &dir,
             "test.txt",
             "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9",
+            HashAlgo::Sha256,
+            &Sandbox::default(),
         );
-        assert!(result.unwrap());
+        assert!(result.unwrap().0);
 
         let _ = std::fs::remove_dir_all(&dir);
     }
@@ -509,8 +1400,82 @@ mod tests {
         let _ = std::fs::create_dir_all(&dir);
         std::fs::write(dir.join("test.txt"), b"hello world").unwrap();
 
-        let result = verify_file(&dir, "test.txt", "0000000000000000");
-        assert!(!result.unwrap());
+        let result = verify_file(&dir, "test.txt", "0000000000000000", HashAlgo::Sha256, &Sandbox::default());
+        assert!(!result.unwrap().0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn verify_file_compressed_decompresses_before_hashing() {
+        let dir = PathBuf::from("target/robustack_test_verify_compressed_gzip");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let content = b"hello world";
+        let compressed = crate::compress::compress_bytes(crate::cli::CompressionMode::Gzip, content)
+            .await
+            .unwrap();
+        std::fs::write(dir.join("test.txt.gz"), &compressed).unwrap();
+
+        let (ok, algo_used) = verify_file_compressed(
+            &dir,
+            "test.txt.gz",
+            &sha256_hex(content),
+            HashAlgo::Sha256,
+            &Sandbox::default(),
+            Some("gzip"),
+        )
+        .await
+        .unwrap();
+        assert!(ok);
+        assert_eq!(algo_used, "sha256");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn verify_file_compressed_with_no_encoding_matches_verify_file() {
+        let dir = PathBuf::from("target/robustack_test_verify_compressed_none");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(dir.join("test.txt"), b"hello world").unwrap();
+
+        let (ok, _) = verify_file_compressed(
+            &dir,
+            "test.txt",
+            &sha256_hex(b"hello world"),
+            HashAlgo::Sha256,
+            &Sandbox::default(),
+            None,
+        )
+        .await
+        .unwrap();
+        assert!(ok);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn verify_file_compressed_catches_tampered_compressed_content() {
+        let dir = PathBuf::from("target/robustack_test_verify_compressed_tampered");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let compressed =
+            crate::compress::compress_bytes(crate::cli::CompressionMode::Zstd, b"original content")
+                .await
+                .unwrap();
+        std::fs::write(dir.join("test.txt.zst"), &compressed).unwrap();
+
+        let (ok, _) = verify_file_compressed(
+            &dir,
+            "test.txt.zst",
+            &sha256_hex(b"different content"),
+            HashAlgo::Sha256,
+            &Sandbox::default(),
+            Some("zstd"),
+        )
+        .await
+        .unwrap();
+        assert!(!ok);
 
         let _ = std::fs::remove_dir_all(&dir);
     }
@@ -522,7 +1487,7 @@ mod tests {
         std::fs::write(dir.join("safe.txt"), b"safe").unwrap();
 
         // Attempting to traverse should be blocked at the input validation layer.
-        let result = verify_file(&dir, "../../etc/passwd", "irrelevant");
+        let result = verify_file(&dir, "../../etc/passwd", "irrelevant", HashAlgo::Sha256, &Sandbox::default());
         let err = result.unwrap_err().to_string();
         assert!(
             err.contains("Path traversal blocked"),
@@ -537,7 +1502,7 @@ mod tests {
         let dir = PathBuf::from("target/robustack_test_verify_empty");
         let _ = std::fs::create_dir_all(&dir);
 
-        let result = verify_file(&dir, "", "irrelevant");
+        let result = verify_file(&dir, "", "irrelevant", HashAlgo::Sha256, &Sandbox::default());
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Empty relative path"));
 
@@ -549,7 +1514,7 @@ mod tests {
         let dir = PathBuf::from("target/robustack_test_verify_abs");
         let _ = std::fs::create_dir_all(&dir);
 
-        let result = verify_file(&dir, "/etc/passwd", "irrelevant");
+        let result = verify_file(&dir, "/etc/passwd", "irrelevant", HashAlgo::Sha256, &Sandbox::default());
         let err = result.unwrap_err().to_string();
         assert!(
             err.contains("Path traversal blocked"),
@@ -563,27 +1528,125 @@ mod tests {
     fn validate_path_fails_outside_cwd() {
         let temp = std::env::temp_dir();
         // This is extremely likely to be outside the CWD (which is the repo root)
-        let result = validate_path_is_safe(&temp);
+        let result = validate_path_is_safe(&temp, &Sandbox::default());
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Path traversal blocked"));
     }
 
+    // -- logical absolutization tests ----------------------------------------
+
     #[test]
-    fn manifest_entries_iterator() {
-        let mut m = Manifest::default();
-        m.insert(ManifestEntry {
-            source_url: "a".into(),
-            sha256: "aaa".into(),
-            local_path: "a.html".into(),
-            size: 1,
-            downloaded_at: "t".into(),
-        });
+    fn absolutize_folds_parent_dir_components() {
+        let cwd = env::current_dir().unwrap();
+        let resolved = absolutize(Path::new("target/a/../b")).unwrap();
+        assert_eq!(resolved, cwd.join("target/b"));
+    }
+
+    #[test]
+    fn absolutize_skips_current_dir_components() {
+        let cwd = env::current_dir().unwrap();
+        let resolved = absolutize(Path::new("./target/./c")).unwrap();
+        assert_eq!(resolved, cwd.join("target/c"));
+    }
+
+    #[test]
+    fn absolutize_does_not_require_path_to_exist() {
+        // No filesystem access happens, so a never-created path resolves fine.
+        let resolved = absolutize(Path::new("target/robustack_never_created_xyz"));
+        assert!(resolved.is_ok());
+    }
+
+    #[test]
+    fn absolutize_leaves_absolute_paths_alone_modulo_dedot() {
+        let resolved = absolutize(Path::new("/a/b/../c")).unwrap();
+        assert_eq!(resolved, PathBuf::from("/a/c"));
+    }
+
+    #[test]
+    fn strip_verbatim_prefix_is_noop_without_prefix() {
+        let path = Path::new("/tmp/plain/path");
+        assert_eq!(strip_verbatim_prefix(path), path.to_path_buf());
+    }
+
+    #[test]
+    fn validate_path_is_safe_allows_not_yet_created_path_inside_cwd() {
+        let dir = PathBuf::from("target/robustack_test_validate_not_yet_created");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // The directory does not exist, so the old canonicalize-based check
+        // would fail outright; the logical check should still succeed.
+        let result = validate_path_is_safe(&dir, &Sandbox::default());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn validate_path_is_safe_still_blocks_nonexistent_path_outside_cwd() {
+        let outside = std::env::temp_dir().join("robustack_never_created_outside_cwd_xyz");
+        let _ = std::fs::remove_dir_all(&outside);
+
+        let result = validate_path_is_safe(&outside, &Sandbox::default());
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Path traversal blocked"));
+    }
+
+    #[test]
+    fn should_skip_tolerates_not_yet_created_output_dir() {
+        let m = Manifest::default();
+        let dir = PathBuf::from("target/robustack_test_should_skip_not_created");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(!should_skip(&m, "abc", &dir, "file.html", &Sandbox::default()));
+    }
+
+    #[test]
+    fn validate_path_is_safe_allows_configured_sandbox_root_outside_cwd() {
+        let outside = std::env::temp_dir().join("robustack_test_sandbox_custom_root");
+        let _ = std::fs::remove_dir_all(&outside);
+
+        // Outside the CWD, this would normally be blocked — but a sandbox
+        // configured to permit it should allow it through.
+        let sandbox = Sandbox::new(vec![std::env::temp_dir()]);
+        let result = validate_path_is_safe(&outside, &sandbox);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn validate_path_is_safe_blocks_paths_outside_every_configured_root() {
+        let sandbox = Sandbox::new(vec![PathBuf::from("target/robustack_test_sandbox_a")]);
+        let outside = std::env::temp_dir().join("robustack_test_sandbox_b");
+        let result = validate_path_is_safe(&outside, &sandbox);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Path traversal blocked"));
+    }
+
+    #[test]
+    fn manifest_entries_iterator() {
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "a".into(),
+            digest: "aaa".into(),
+            local_path: "a.html".into(),
+            size: 1,
+            downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
         m.insert(ManifestEntry {
             source_url: "b".into(),
-            sha256: "bbb".into(),
+            digest: "bbb".into(),
             local_path: "b.html".into(),
             size: 2,
             downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
         });
         assert_eq!(m.entries().count(), 2);
     }
@@ -626,4 +1689,727 @@ mod tests {
         );
         assert_eq!(path, PathBuf::from("b94d27b9934d3e08_passwd"));
     }
+
+    // -- parse_manifest tests ----------------------------------------------
+
+    #[test]
+    fn parse_manifest_roundtrips_save_format() {
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/c".into(),
+            digest: "feedface".into(),
+            local_path: "c.html".into(),
+            size: 64,
+            downloaded_at: "2026-02-15T02:00:00Z".into(),
+            slug: Some("c".into()),
+            post_date: Some("2026-02-14".into()),
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+        let json = serde_json::to_string(&m).unwrap();
+
+        let parsed = parse_manifest(&json).expect("valid manifest should parse");
+        assert_eq!(parsed.len(), 1);
+        assert!(parsed.contains("feedface"));
+    }
+
+    #[test]
+    fn parse_manifest_rejects_garbage_without_panicking() {
+        let result = parse_manifest("not json at all {{{");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_manifest_tolerates_missing_optional_fields() {
+        // Entries recorded before `slug`/`post_date` existed should still load.
+        let legacy = r#"{
+            "version": 1,
+            "generated_at": "2026-02-15T00:00:00Z",
+            "entries": [{
+                "source_url": "https://x.com/legacy",
+                "sha256": "aaaa",
+                "local_path": "legacy.html",
+                "size": 10,
+                "downloaded_at": "2026-02-15T00:00:00Z"
+            }]
+        }"#;
+        let parsed = parse_manifest(legacy).expect("legacy manifest should still parse");
+        assert_eq!(parsed.len(), 1);
+        let entry = parsed.entries().next().unwrap();
+        assert!(entry.slug.is_none());
+        assert_eq!(entry.digest, "aaaa");
+        assert_eq!(entry.algo, HashAlgo::Sha256);
+    }
+
+    // -- SRI-style multi-algorithm digest tests ------------------------------
+
+    #[test]
+    fn looks_like_sri_spec_detects_dashed_tokens() {
+        assert!(looks_like_sri_spec("sha256-abcd"));
+        assert!(!looks_like_sri_spec(
+            "b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9"
+        ));
+    }
+
+    #[test]
+    fn parse_sri_spec_parses_single_token() {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(b"fake-digest");
+        let tokens = parse_sri_spec(&format!("sha256-{encoded}")).unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].algo, SriAlgo::Sha256);
+        assert_eq!(tokens[0].expected, b"fake-digest");
+    }
+
+    #[test]
+    fn parse_sri_spec_parses_multiple_whitespace_separated_tokens() {
+        let a = base64::engine::general_purpose::STANDARD.encode(b"aaa");
+        let b = base64::engine::general_purpose::STANDARD.encode(b"bbb");
+        let tokens = parse_sri_spec(&format!("sha256-{a} sha512-{b}")).unwrap();
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[1].algo, SriAlgo::Sha512);
+    }
+
+    #[test]
+    fn parse_sri_spec_rejects_unknown_algorithm() {
+        assert!(parse_sri_spec("md5-abcd").is_err());
+    }
+
+    #[test]
+    fn parse_sri_spec_rejects_malformed_base64() {
+        assert!(parse_sri_spec("sha256-not_valid_base64!!!").is_err());
+    }
+
+    #[test]
+    fn verify_file_accepts_sri_sha384_digest() {
+        let dir = PathBuf::from("target/robustack_test_verify_sri_sha384");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(dir.join("test.txt"), b"hello world").unwrap();
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(Sha384::digest(b"hello world"));
+        let spec = format!("sha384-{encoded}");
+        let (ok, algo_used) =
+            verify_file(&dir, "test.txt", &spec, HashAlgo::Sha256, &Sandbox::default()).unwrap();
+        assert!(ok);
+        assert_eq!(algo_used, "sha384");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verify_file_sri_picks_strongest_of_multiple_tokens() {
+        let dir = PathBuf::from("target/robustack_test_verify_sri_multi");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(dir.join("test.txt"), b"hello world").unwrap();
+
+        let wrong_sha256 = base64::engine::general_purpose::STANDARD.encode(b"not it");
+        let right_sha512 =
+            base64::engine::general_purpose::STANDARD.encode(Sha512::digest(b"hello world"));
+        let spec = format!("sha256-{wrong_sha256} sha512-{right_sha512}");
+        let (ok, algo_used) =
+            verify_file(&dir, "test.txt", &spec, HashAlgo::Sha256, &Sandbox::default()).unwrap();
+        assert!(ok);
+        assert_eq!(algo_used, "sha512");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verify_file_rejects_malformed_sri_spec_instead_of_skipping() {
+        let dir = PathBuf::from("target/robustack_test_verify_sri_malformed");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(dir.join("test.txt"), b"hello world").unwrap();
+
+        let result = verify_file(
+            &dir,
+            "test.txt",
+            "sha1-deadbeef",
+            HashAlgo::Sha256,
+            &Sandbox::default(),
+        );
+        assert!(result.is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // -- pluggable hash backend tests ---------------------------------------
+
+    #[test]
+    fn digest_hex_sha256_matches_sha256_hex() {
+        assert_eq!(
+            digest_hex(HashAlgo::Sha256, b"hello world"),
+            sha256_hex(b"hello world")
+        );
+    }
+
+    #[test]
+    fn digest_hex_blake3_differs_from_sha256() {
+        let sha = digest_hex(HashAlgo::Sha256, b"hello world");
+        let blake = digest_hex(HashAlgo::Blake3, b"hello world");
+        assert_ne!(sha, blake);
+        assert_eq!(blake.len(), 64);
+        // Deterministic: hashing the same input twice must agree.
+        assert_eq!(blake, digest_hex(HashAlgo::Blake3, b"hello world"));
+    }
+
+    #[test]
+    fn digest_file_blake3_matches_in_memory_digest() {
+        let dir = PathBuf::from("target/robustack_test_digest_file_blake3");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(dir.join("test.txt"), b"hello world").unwrap();
+
+        let from_file = digest_file(HashAlgo::Blake3, &dir.join("test.txt")).unwrap();
+        assert_eq!(from_file, digest_hex(HashAlgo::Blake3, b"hello world"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compute_sri_round_trips_through_verify_file() {
+        let dir = PathBuf::from("target/robustack_test_compute_sri_round_trip");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(dir.join("test.txt"), b"hello world").unwrap();
+
+        let sri = compute_sri(b"hello world");
+        assert!(sri.starts_with("sha256-"));
+        assert!(sri.contains(" sha384-"));
+
+        let sandbox = Sandbox::default();
+        let (ok, algo_used) =
+            verify_file(&dir, "test.txt", &sri, HashAlgo::Sha256, &sandbox).unwrap();
+        assert!(ok);
+        assert_eq!(algo_used, "sha384"); // verify_file prefers the strongest token present
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verify_file_picks_algorithm_from_argument() {
+        let dir = PathBuf::from("target/robustack_test_verify_blake3");
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(dir.join("test.txt"), b"hello world").unwrap();
+
+        let digest = digest_hex(HashAlgo::Blake3, b"hello world");
+        let result = verify_file(&dir, "test.txt", &digest, HashAlgo::Blake3, &Sandbox::default());
+        assert!(result.unwrap().0);
+
+        // The same file fails to verify against its SHA-256 digest when
+        // checked as BLAKE3, and vice versa — mixed-algorithm entries in
+        // the same directory must each be checked with their own `algo`.
+        let sha = digest_hex(HashAlgo::Sha256, b"hello world");
+        let result = verify_file(&dir, "test.txt", &sha, HashAlgo::Blake3, &Sandbox::default());
+        assert!(!result.unwrap().0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // -- merkle root tests ---------------------------------------------------
+
+    #[test]
+    fn merkle_root_empty_manifest_is_none() {
+        let m = Manifest::default();
+        assert_eq!(m.merkle_root(), None);
+    }
+
+    #[test]
+    fn merkle_root_single_entry_is_the_leaf() {
+        let mut m = Manifest::default();
+        let entry = ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest: "deadbeef".into(),
+            local_path: "a.html".into(),
+            size: 1,
+            downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        };
+        let leaf = hex::encode(merkle_leaf(&entry));
+        m.insert(entry);
+        assert_eq!(m.merkle_root(), Some(leaf));
+    }
+
+    #[test]
+    fn merkle_root_is_independent_of_insertion_order() {
+        let mut forward = Manifest::default();
+        let mut backward = Manifest::default();
+        for path in ["a.html", "b.html", "c.html"] {
+            let entry = ManifestEntry {
+                source_url: format!("https://x.com/{path}"),
+                digest: format!("digest-{path}"),
+                local_path: path.into(),
+                size: 1,
+                downloaded_at: "t".into(),
+                slug: None,
+                post_date: None,
+                algo: HashAlgo::Sha256,
+                compressed_size: None,
+                encoding: None,
+                sri: None,
+            };
+            forward.insert(entry);
+        }
+        for path in ["c.html", "b.html", "a.html"] {
+            let entry = ManifestEntry {
+                source_url: format!("https://x.com/{path}"),
+                digest: format!("digest-{path}"),
+                local_path: path.into(),
+                size: 1,
+                downloaded_at: "t".into(),
+                slug: None,
+                post_date: None,
+                algo: HashAlgo::Sha256,
+                compressed_size: None,
+                encoding: None,
+                sri: None,
+            };
+            backward.insert(entry);
+        }
+        assert_eq!(forward.merkle_root(), backward.merkle_root());
+    }
+
+    #[test]
+    fn merkle_root_changes_with_content() {
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest: "aaa".into(),
+            local_path: "a.html".into(),
+            size: 1,
+            downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+        let before = m.merkle_root();
+
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/b".into(),
+            digest: "bbb".into(),
+            local_path: "b.html".into(),
+            size: 1,
+            downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+        let after = m.merkle_root();
+
+        assert_ne!(before, after);
+    }
+
+    #[test]
+    fn merkle_root_handles_odd_entry_count_by_duplicating_last_node() {
+        // Three entries: the tree must duplicate the third leaf to pair it,
+        // rather than panicking or dropping it.
+        let mut m = Manifest::default();
+        for path in ["a.html", "b.html", "c.html"] {
+            m.insert(ManifestEntry {
+                source_url: format!("https://x.com/{path}"),
+                digest: format!("digest-{path}"),
+                local_path: path.into(),
+                size: 1,
+                downloaded_at: "t".into(),
+                slug: None,
+                post_date: None,
+                algo: HashAlgo::Sha256,
+                compressed_size: None,
+                encoding: None,
+                sri: None,
+            });
+        }
+        assert!(m.merkle_root().is_some());
+    }
+
+    #[test]
+    fn save_persists_merkle_root() {
+        let dir = PathBuf::from("target/robustack_test_merkle_save");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest: "deadbeef".into(),
+            local_path: "a.html".into(),
+            size: 1,
+            downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+        let expected_root = m.merkle_root();
+
+        m.save(&dir, &Sandbox::default()).unwrap();
+        let loaded = Manifest::load_or_create(&dir, &Sandbox::default()).unwrap();
+        assert_eq!(loaded.merkle_root, expected_root);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verify_all_passes_for_intact_archive() {
+        let dir = PathBuf::from("target/robustack_test_verify_all_ok");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let content = b"intact contents";
+        let digest = sha256_hex(content);
+        std::fs::write(dir.join("a.html"), content).unwrap();
+
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest,
+            local_path: "a.html".into(),
+            size: content.len() as u64,
+            downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+        m.save(&dir, &Sandbox::default()).unwrap();
+
+        let loaded = Manifest::load_or_create(&dir, &Sandbox::default()).unwrap();
+        assert!(loaded.verify_all(&dir, &Sandbox::default()).unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verify_all_detects_missing_file() {
+        let dir = PathBuf::from("target/robustack_test_verify_all_missing");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest: "deadbeef".into(),
+            local_path: "missing.html".into(),
+            size: 1,
+            downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+
+        assert!(!m.verify_all(&dir, &Sandbox::default()).unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verify_all_detects_silent_corruption() {
+        let dir = PathBuf::from("target/robustack_test_verify_all_corrupt");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let content = b"original contents";
+        let digest = sha256_hex(content);
+        std::fs::write(dir.join("a.html"), content).unwrap();
+
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest,
+            local_path: "a.html".into(),
+            size: content.len() as u64,
+            downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+        m.save(&dir, &Sandbox::default()).unwrap();
+
+        // Tamper with the file after the manifest was saved.
+        std::fs::write(dir.join("a.html"), b"tampered contents").unwrap();
+
+        let loaded = Manifest::load_or_create(&dir, &Sandbox::default()).unwrap();
+        assert!(!loaded.verify_all(&dir, &Sandbox::default()).unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // -- signing tests --------------------------------------------------------
+
+    fn test_signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    #[test]
+    fn save_signed_then_load_verified_succeeds() {
+        let dir = PathBuf::from("target/robustack_test_signed_ok");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let signing_key = test_signing_key();
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest: "deadbeef".into(),
+            local_path: "a.html".into(),
+            size: 1,
+            downloaded_at: "t".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+        m.save_signed(&dir, &signing_key, &Sandbox::default()).unwrap();
+
+        assert!(dir.join("manifest.json.sig").exists());
+
+        let loaded = Manifest::load_verified(&dir, &signing_key.verifying_key(), &Sandbox::default()).unwrap();
+        assert!(loaded.contains("deadbeef"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_verified_rejects_missing_signature() {
+        let dir = PathBuf::from("target/robustack_test_signed_missing_sig");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let signing_key = test_signing_key();
+        let mut m = Manifest::default();
+        // Plain save(), no signature written.
+        m.save(&dir, &Sandbox::default()).unwrap();
+
+        let result = Manifest::load_verified(&dir, &signing_key.verifying_key(), &Sandbox::default());
+        assert!(result.is_err());
+        assert!(
+            result.unwrap_err().to_string().contains("Missing detached signature"),
+            "expected a missing-signature error"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_verified_rejects_tampered_manifest() {
+        let dir = PathBuf::from("target/robustack_test_signed_tampered");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let signing_key = test_signing_key();
+        let mut m = Manifest::default();
+        m.save_signed(&dir, &signing_key, &Sandbox::default()).unwrap();
+
+        // Tamper with the manifest after it was signed.
+        let mut tampered = std::fs::read_to_string(dir.join("manifest.json")).unwrap();
+        tampered.push('\n');
+        std::fs::write(dir.join("manifest.json"), tampered).unwrap();
+
+        let result = Manifest::load_verified(&dir, &signing_key.verifying_key(), &Sandbox::default());
+        assert!(result.is_err());
+        assert!(
+            result.unwrap_err().to_string().contains("Signature verification failed"),
+            "expected a signature verification error"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_verified_rejects_wrong_public_key() {
+        let dir = PathBuf::from("target/robustack_test_signed_wrong_key");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let signing_key = test_signing_key();
+        let other_key = SigningKey::from_bytes(&[9u8; 32]);
+        let mut m = Manifest::default();
+        m.save_signed(&dir, &signing_key, &Sandbox::default()).unwrap();
+
+        let result = Manifest::load_verified(&dir, &other_key.verifying_key(), &Sandbox::default());
+        assert!(result.is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_or_create_does_not_check_signature() {
+        // The explicit opt-out path: load_or_create never checks for a
+        // signature at all, signed or not.
+        let dir = PathBuf::from("target/robustack_test_signed_downgrade");
+        let _ = std::fs::create_dir_all(&dir);
+
+        let signing_key = test_signing_key();
+        let mut m = Manifest::default();
+        m.save_signed(&dir, &signing_key, &Sandbox::default()).unwrap();
+
+        let loaded = Manifest::load_or_create(&dir, &Sandbox::default()).unwrap();
+        assert!(loaded.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    // -- tar export/import tests ----------------------------------------------
+
+    #[test]
+    fn export_tar_then_import_tar_roundtrips() {
+        let src_dir = PathBuf::from("target/robustack_test_tar_export_src");
+        let dest_dir = PathBuf::from("target/robustack_test_tar_export_dest");
+        let _ = std::fs::create_dir_all(&src_dir);
+        let _ = std::fs::create_dir_all(&dest_dir);
+
+        let content = b"export me";
+        let digest = sha256_hex(content);
+        std::fs::write(src_dir.join("a.html"), content).unwrap();
+
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest,
+            local_path: "a.html".into(),
+            size: content.len() as u64,
+            downloaded_at: "2026-02-15T00:00:00Z".into(),
+            slug: Some("a".into()),
+            post_date: Some("2026-02-14".into()),
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+        m.save(&src_dir, &Sandbox::default()).unwrap();
+
+        let mut tar_bytes = Vec::new();
+        export_tar(&m, &src_dir, &mut tar_bytes, &Sandbox::default()).unwrap();
+
+        let imported = import_tar(tar_bytes.as_slice(), &dest_dir, &Sandbox::default()).unwrap();
+        assert!(imported.contains(&sha256_hex(content)));
+        let entry = imported.entries().next().unwrap();
+        assert_eq!(entry.local_path, "a.html");
+        assert_eq!(entry.source_url, "https://x.com/a");
+        assert_eq!(entry.slug.as_deref(), Some("a"));
+        assert_eq!(std::fs::read(dest_dir.join("a.html")).unwrap(), content);
+
+        let _ = std::fs::remove_dir_all(&src_dir);
+        let _ = std::fs::remove_dir_all(&dest_dir);
+    }
+
+    #[test]
+    fn export_tar_rejects_corrupted_store() {
+        let dir = PathBuf::from("target/robustack_test_tar_export_corrupt");
+        let _ = std::fs::create_dir_all(&dir);
+
+        std::fs::write(dir.join("a.html"), b"tampered on disk").unwrap();
+
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest: sha256_hex(b"original content"),
+            local_path: "a.html".into(),
+            size: 17,
+            downloaded_at: "2026-02-15T00:00:00Z".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+
+        let mut tar_bytes = Vec::new();
+        let result = export_tar(&m, &dir, &mut tar_bytes, &Sandbox::default());
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("fails digest verification"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_tar_rejects_path_traversal_member() {
+        let dest_dir = PathBuf::from("target/robustack_test_tar_import_traversal");
+        let _ = std::fs::create_dir_all(&dest_dir);
+
+        let mut tar_bytes = Vec::new();
+        {
+            let mut builder = tar::Builder::new(&mut tar_bytes);
+            let mut header = tar::Header::new_gnu();
+            let data = b"evil";
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, "../../etc/passwd", data.as_slice())
+                .unwrap();
+            builder.finish().unwrap();
+        }
+
+        let result = import_tar(tar_bytes.as_slice(), &dest_dir, &Sandbox::default());
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("unsafe path"));
+
+        let _ = std::fs::remove_dir_all(&dest_dir);
+    }
+
+    #[test]
+    fn import_tar_rehashes_rather_than_trusting_manifest() {
+        let dest_dir = PathBuf::from("target/robustack_test_tar_import_rehash");
+        let _ = std::fs::create_dir_all(&dest_dir);
+
+        // Build a tar by hand whose embedded manifest.json claims a digest
+        // that does not match the file's actual bytes.
+        let mut m = Manifest::default();
+        m.insert(ManifestEntry {
+            source_url: "https://x.com/a".into(),
+            digest: "0000000000000000000000000000000000000000000000000000000000000000".into(),
+            local_path: "a.html".into(),
+            size: 4,
+            downloaded_at: "2026-02-15T00:00:00Z".into(),
+            slug: None,
+            post_date: None,
+            algo: HashAlgo::Sha256,
+            compressed_size: None,
+            encoding: None,
+            sri: None,
+        });
+        let manifest_json = serde_json::to_vec_pretty(&m).unwrap();
+
+        let mut tar_bytes = Vec::new();
+        {
+            let mut builder = tar::Builder::new(&mut tar_bytes);
+            let mut header = tar::Header::new_gnu();
+            header.set_size(manifest_json.len() as u64);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, MANIFEST_FILENAME, manifest_json.as_slice())
+                .unwrap();
+
+            let data = b"real";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            builder.append_data(&mut header, "a.html", data.as_slice()).unwrap();
+            builder.finish().unwrap();
+        }
+
+        let imported = import_tar(tar_bytes.as_slice(), &dest_dir, &Sandbox::default()).unwrap();
+        let entry = imported.entries().next().unwrap();
+        assert_eq!(entry.digest, sha256_hex(b"real"));
+        assert_ne!(
+            entry.digest,
+            "0000000000000000000000000000000000000000000000000000000000000000"
+        );
+
+        let _ = std::fs::remove_dir_all(&dest_dir);
+    }
 }
@@ -28,12 +28,16 @@
 // Stub modules are scaffolded but not yet wired to subcommands.
 #![allow(dead_code)]
 
+mod cache;
 mod cli;
 mod client;
+mod compress;
 mod config;
 mod handlers;
+mod http_cache;
 mod integrity;
 mod processor;
+mod sniff;
 
 use cli::{Cli, Commands};
 use client::ReqwestClient;
@@ -56,17 +60,20 @@ async fn main() -> anyhow::Result<()> {
     // Step 2: Dispatch to handler.
     match cli.command {
         Commands::Download(ref args) => {
-            let config = AppConfig::from_cli(&cli, args.limit, Some(args));
+            let config = AppConfig::from_cli(&cli, Some(args))?;
             let http_client = ReqwestClient::from_config(&config);
             handlers::download::run(&args.url, &config, &http_client).await?;
         }
         Commands::List(ref args) => {
-            let config = AppConfig::from_cli(&cli, args.limit, None);
+            let config = AppConfig::from_cli(&cli, None)?;
             let http_client = ReqwestClient::from_config(&config);
             handlers::list::run(&args.url, &config, &http_client).await?;
         }
         Commands::Audit(ref args) => {
-            handlers::audit::run(&args.manifest)?;
+            handlers::audit::run(&args.manifest, args.verify, args.verify_key.as_deref()).await?;
+        }
+        Commands::Serve(ref args) => {
+            handlers::serve::run(args).await?;
         }
         Commands::Completions(ref args) => {
             cli::print_completions(args.shell);
@@ -17,6 +17,179 @@
 //! All functions are **pure** — they accept input and return output with
 //! no side effects (no I/O, no network, no filesystem writes).
 //! This makes the processor fully testable without mocks.
+//!
+//! # Readability extraction
+//! [`extract_article`] scores candidate block containers (`div`,
+//! `section`, `article`, `main`, `td`) by text-density and paragraph
+//! count, picks the highest-scoring one, and re-serializes it with any
+//! nested navigation/footer/subscribe/share/comment/promo elements
+//! dropped. Run ahead of [`html_to_markdown`]/[`html_to_text`] when
+//! `--readability` is set; left alone otherwise, and skipped entirely for
+//! `html`/`epub` output, which keep the raw document.
+
+use scraper::{ElementRef, Html, Selector};
+
+/// Tags considered structural chrome rather than article content, always
+/// excluded regardless of their class/id.
+const NOISE_TAGS: &[&str] = &["nav", "footer", "aside", "script", "style", "form"];
+
+/// Substrings checked (case-insensitively) against an element's `class`/
+/// `id` to catch chrome that isn't marked up with a dedicated tag, e.g.
+/// Substack's `<div class="subscribe-widget">`.
+const NOISE_HINTS: &[&str] = &["subscribe", "share", "comment", "promo"];
+
+/// HTML5 void elements — rendered without a closing tag or children.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param",
+    "source", "track", "wbr",
+];
+
+/// CSS selector list for candidate article containers, widest net first
+/// (`body` is deliberately excluded — it's never a useful subtree, just a
+/// fallback for when nothing scores).
+const CANDIDATE_SELECTOR: &str = "div, section, article, main, td";
+
+/// Extract the primary article body from `html`, dropping navigation,
+/// footers, and subscribe/share/comment/promo widgets.
+///
+/// Candidate containers are scored by text-density — text length divided
+/// by the number of descendant tags and links — weighted by paragraph
+/// count, and the highest-scoring one is kept. Falls back to returning
+/// `html` unchanged if no candidate has any paragraph content (e.g. a
+/// plain-text or already-minimal document), so this is safe to run ahead
+/// of every conversion without a dedicated "did it work" check.
+#[must_use]
+pub fn extract_article(html: &str) -> String {
+    if html.trim().is_empty() {
+        return String::new();
+    }
+
+    let document = Html::parse_document(html);
+    let (Ok(body_selector), Ok(candidate_selector)) =
+        (Selector::parse("body"), Selector::parse(CANDIDATE_SELECTOR))
+    else {
+        return html.to_owned();
+    };
+    let Some(body) = document.select(&body_selector).next() else {
+        return html.to_owned();
+    };
+
+    let mut best: Option<(f64, ElementRef)> = None;
+    for candidate in body.select(&candidate_selector) {
+        if is_noise(&candidate) {
+            continue;
+        }
+        let stats = collect_stats(candidate);
+        if stats.paragraph_count == 0 {
+            continue;
+        }
+        let density = stats.text_len as f64 / (stats.tag_count + stats.link_count).max(1) as f64;
+        let candidate_score = density * (stats.paragraph_count as f64 + 1.0);
+        let is_better = best.map_or(true, |(best_score, _)| candidate_score > best_score);
+        if is_better {
+            best = Some((candidate_score, candidate));
+        }
+    }
+
+    best.map_or_else(|| html.to_owned(), |(_, el)| render(el))
+}
+
+/// Per-candidate accumulators used to compute a text-density score.
+#[derive(Default)]
+struct ContentStats {
+    text_len: usize,
+    tag_count: usize,
+    link_count: usize,
+    paragraph_count: usize,
+}
+
+/// Returns `true` if `el` itself should be excluded from both scoring and
+/// the final rendered output — its entire subtree is skipped wherever
+/// this is checked.
+fn is_noise(el: &ElementRef) -> bool {
+    if NOISE_TAGS.contains(&el.value().name()) {
+        return true;
+    }
+    let class = el.value().attr("class").unwrap_or("").to_lowercase();
+    let id = el.value().attr("id").unwrap_or("").to_lowercase();
+    NOISE_HINTS
+        .iter()
+        .any(|hint| class.contains(hint) || id.contains(hint))
+}
+
+/// Walk `el`'s descendants, skipping any noise subtree, accumulating text
+/// length, tag/link counts, and paragraph count.
+fn collect_stats(el: ElementRef) -> ContentStats {
+    let mut stats = ContentStats::default();
+    accumulate_stats(el, &mut stats);
+    stats
+}
+
+fn accumulate_stats(el: ElementRef, stats: &mut ContentStats) {
+    for child in el.children() {
+        if let Some(child_el) = ElementRef::wrap(child) {
+            if is_noise(&child_el) {
+                continue;
+            }
+            stats.tag_count += 1;
+            match child_el.value().name() {
+                "a" => stats.link_count += 1,
+                "p" => stats.paragraph_count += 1,
+                _ => {}
+            }
+            accumulate_stats(child_el, stats);
+        } else if let Some(text) = child.value().as_text() {
+            stats.text_len += text.trim().len();
+        }
+    }
+}
+
+/// Re-serialize `el`, dropping any noise subtree found along the way.
+fn render(el: ElementRef) -> String {
+    let mut out = String::new();
+    render_into(el, &mut out);
+    out
+}
+
+fn render_into(el: ElementRef, out: &mut String) {
+    let name = el.value().name();
+    out.push('<');
+    out.push_str(name);
+    for (attr_name, attr_value) in el.value().attrs() {
+        out.push(' ');
+        out.push_str(attr_name);
+        out.push_str("=\"");
+        out.push_str(&attr_value.replace('"', "&quot;"));
+        out.push('"');
+    }
+    out.push('>');
+    if VOID_ELEMENTS.contains(&name) {
+        return;
+    }
+    for child in el.children() {
+        if let Some(child_el) = ElementRef::wrap(child) {
+            if is_noise(&child_el) {
+                continue;
+            }
+            render_into(child_el, out);
+        } else if let Some(text) = child.value().as_text() {
+            out.push_str(&escape_text(text));
+        }
+    }
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+}
+
+/// Escape the characters unsafe in HTML text content.
+///
+/// `scraper` text nodes hold already-decoded content (`&amp;` parses to
+/// `&`), so re-serializing it verbatim would turn e.g. a literal `<div>`
+/// in prose into live markup that a later `html_to_markdown`/`html_to_text`
+/// pass then mis-parses as structure.
+fn escape_text(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
 
 /// Convert raw HTML to Markdown.
 ///
@@ -90,6 +263,57 @@ mod tests {
         assert_eq!(html_to_text(""), "");
     }
 
+    #[test]
+    fn extract_article_picks_the_densest_paragraph_container() {
+        let html = "<html><body>\
+            <nav><a href=\"/\">Home</a><a href=\"/about\">About</a></nav>\
+            <div class=\"post-content\">\
+                <p>This is the first real paragraph of the article, with enough text to win.</p>\
+                <p>And a second paragraph, continuing the actual article body at length.</p>\
+            </div>\
+            <footer>Copyright 2026</footer>\
+            </body></html>";
+        let extracted = extract_article(html);
+        assert!(extracted.contains("first real paragraph"), "{extracted}");
+        assert!(extracted.contains("second paragraph"), "{extracted}");
+        assert!(!extracted.contains("Copyright"), "footer should be dropped: {extracted}");
+        assert!(!extracted.contains("Home"), "nav links should be dropped: {extracted}");
+    }
+
+    #[test]
+    fn extract_article_strips_nested_subscribe_and_comment_widgets() {
+        let html = "<html><body><div class=\"content\">\
+            <p>Real article text, long enough to actually score as content here.</p>\
+            <div class=\"subscribe-widget\"><p>Subscribe now for more!</p></div>\
+            <div id=\"comments-section\"><p>A reader comment goes here.</p></div>\
+            </div></body></html>";
+        let extracted = extract_article(html);
+        assert!(extracted.contains("Real article text"), "{extracted}");
+        assert!(!extracted.contains("Subscribe now"), "{extracted}");
+        assert!(!extracted.contains("reader comment"), "{extracted}");
+    }
+
+    #[test]
+    fn extract_article_escapes_reserved_characters_in_text() {
+        let html = "<html><body><div class=\"content\">\
+            <p>Q &amp; A: &lt;div&gt;example&lt;/div&gt;, plus enough text to win scoring.</p>\
+            </div></body></html>";
+        let extracted = extract_article(html);
+        assert!(extracted.contains("Q &amp; A: &lt;div&gt;example&lt;/div&gt;"), "{extracted}");
+        assert!(!extracted.contains("<div>example</div>"), "bare tags must stay escaped: {extracted}");
+    }
+
+    #[test]
+    fn extract_article_falls_back_to_original_when_nothing_scores() {
+        let html = "<html><body><span>just a short span, no paragraphs at all</span></body></html>";
+        assert_eq!(extract_article(html), html);
+    }
+
+    #[test]
+    fn extract_article_empty_input() {
+        assert_eq!(extract_article(""), "");
+    }
+
     #[test]
     fn append_source_url_adds_footer() {
         let content = "Hello world";
@@ -0,0 +1,210 @@
+//! @project       RoBustack-DL
+//! @organization  CodeBush Collective
+//! @license       GPL-3.0-only
+//! ---------------------------------------------------------------------------
+//! AI PROVENANCE & HUMAN-IN-THE-LOOP (HITL) METADATA:
+//! - Prompt Engineering: Gemini 3 Flash (Strategy, Scoping & Context Tuning)
+//! - Code Generation:   Gemini 3 Pro (Core Systems Engineering & Async Logic)
+//! - Technical Review:  Claude 4.6 Opus (Security Audit & Idiomatic Refinement)
+//! - HITL Verification: Collisio-Adolebitque - AA0614550BDC21F1 (Manual Audit & Final Validation)
+//! ---------------------------------------------------------------------------
+//! Verified Date: 2026-07-30
+//! Integrity: GPG-Signed | HITL-Certified
+//!
+//! Content sniffing — picks a canonical file extension for a downloaded
+//! asset whose URL is extensionless or whose server `Content-Type` is
+//! missing or generic.
+//!
+//! # Design
+//! The server-reported `Content-Type` wins when it names a specific,
+//! known MIME type. Otherwise (no header, or a generic type like
+//! `application/octet-stream`) the first bytes of the body are checked
+//! against a table of magic-number signatures, modeled on browser content
+//! sniffing. Unresolvable input falls back to `"bin"` rather than leaving
+//! the file nameless.
+
+/// Number of leading bytes inspected for magic-number signatures.
+const SNIFF_WINDOW: usize = 512;
+
+/// Resolve the canonical extension (no leading dot) for a downloaded
+/// asset, preferring `content_type` when it is specific and falling back
+/// to sniffing `bytes`' magic number otherwise.
+#[must_use]
+pub fn sniff_extension(content_type: Option<&str>, bytes: &[u8]) -> &'static str {
+    if let Some(ext) = content_type.and_then(extension_from_mime) {
+        return ext;
+    }
+    sniff_signature(bytes).unwrap_or("bin")
+}
+
+/// Map an extension — as returned by [`sniff_extension`] or declared by a
+/// caller that already knows the expected resource kind (e.g. a `<script>`
+/// tag) — back to a MIME type for embedding in a `data:` URI.
+#[must_use]
+pub fn mime_for_extension(ext: &str) -> &'static str {
+    match ext {
+        "png" => "image/png",
+        "jpg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "pdf" => "application/pdf",
+        "gz" => "application/gzip",
+        "html" => "text/html",
+        "txt" => "text/plain",
+        "css" => "text/css",
+        "js" => "application/javascript",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Map a `Content-Type` header value to an extension, ignoring any
+/// `; charset=...` parameter. Returns `None` for generic or unrecognized
+/// types so the caller falls back to signature sniffing.
+fn extension_from_mime(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    match mime.as_str() {
+        "image/png" => Some("png"),
+        "image/jpeg" => Some("jpg"),
+        "image/gif" => Some("gif"),
+        "image/webp" => Some("webp"),
+        "application/pdf" => Some("pdf"),
+        "application/gzip" | "application/x-gzip" => Some("gz"),
+        "text/html" => Some("html"),
+        "text/plain" => Some("txt"),
+        "text/css" => Some("css"),
+        "application/javascript" | "text/javascript" => Some("js"),
+        _ => None,
+    }
+}
+
+/// Identify `bytes` by magic-number signature, tolerating a body shorter
+/// than the signature being checked.
+fn sniff_signature(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG") {
+        return Some("png");
+    }
+    if bytes.starts_with(b"\xFF\xD8\xFF") {
+        return Some("jpg");
+    }
+    if bytes.starts_with(b"GIF8") {
+        return Some("gif");
+    }
+    if bytes.starts_with(b"%PDF") {
+        return Some("pdf");
+    }
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("webp");
+    }
+    if bytes.starts_with(b"\x1F\x8B") {
+        return Some("gz");
+    }
+    if looks_like_html(bytes) {
+        return Some("html");
+    }
+    None
+}
+
+/// Returns `true` if the leading bytes of `bytes` look like HTML, tolerating
+/// a UTF-8 BOM and leading whitespace before `<html` or `<!doctype`.
+fn looks_like_html(bytes: &[u8]) -> bool {
+    let window = &bytes[..bytes.len().min(SNIFF_WINDOW)];
+    let window = window.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(window);
+    let text = String::from_utf8_lossy(window);
+    let trimmed = text.trim_start().to_ascii_lowercase();
+    trimmed.starts_with("<html") || trimmed.starts_with("<!doctype")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_specific_content_type_over_sniffing() {
+        assert_eq!(sniff_extension(Some("image/png"), b"not actually png bytes"), "png");
+    }
+
+    #[test]
+    fn content_type_with_charset_parameter_is_parsed() {
+        assert_eq!(sniff_extension(Some("text/html; charset=utf-8"), b""), "html");
+    }
+
+    #[test]
+    fn falls_back_to_sniffing_for_octet_stream() {
+        assert_eq!(
+            sniff_extension(Some("application/octet-stream"), b"\x89PNG\r\n\x1a\n"),
+            "png"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_sniffing_when_content_type_missing() {
+        assert_eq!(sniff_extension(None, b"\xFF\xD8\xFF\xE0"), "jpg");
+    }
+
+    #[test]
+    fn sniffs_gif() {
+        assert_eq!(sniff_extension(None, b"GIF89a"), "gif");
+    }
+
+    #[test]
+    fn sniffs_pdf() {
+        assert_eq!(sniff_extension(None, b"%PDF-1.4"), "pdf");
+    }
+
+    #[test]
+    fn sniffs_webp() {
+        let mut bytes = b"RIFF".to_vec();
+        bytes.extend_from_slice(&[0u8; 4]); // file size, irrelevant to sniffing
+        bytes.extend_from_slice(b"WEBP");
+        assert_eq!(sniff_extension(None, &bytes), "webp");
+    }
+
+    #[test]
+    fn sniffs_gzip() {
+        assert_eq!(sniff_extension(None, b"\x1F\x8B\x08\x00"), "gz");
+    }
+
+    #[test]
+    fn sniffs_html_with_doctype_and_leading_whitespace() {
+        assert_eq!(sniff_extension(None, b"  \n<!doctype html><html></html>"), "html");
+    }
+
+    #[test]
+    fn sniffs_html_with_bom() {
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice(b"<html><body>hi</body></html>");
+        assert_eq!(sniff_extension(None, &bytes), "html");
+    }
+
+    #[test]
+    fn unrecognized_content_defaults_to_bin() {
+        assert_eq!(sniff_extension(None, b"\x00\x01\x02\x03"), "bin");
+    }
+
+    #[test]
+    fn empty_body_defaults_to_bin() {
+        assert_eq!(sniff_extension(None, b""), "bin");
+    }
+
+    #[test]
+    fn text_css_content_type_sniffs_as_css() {
+        assert_eq!(sniff_extension(Some("text/css; charset=utf-8"), b""), "css");
+    }
+
+    #[test]
+    fn javascript_content_type_sniffs_as_js() {
+        assert_eq!(sniff_extension(Some("application/javascript"), b""), "js");
+    }
+
+    #[test]
+    fn mime_for_extension_round_trips_known_extensions() {
+        assert_eq!(mime_for_extension("png"), "image/png");
+        assert_eq!(mime_for_extension("css"), "text/css");
+        assert_eq!(mime_for_extension("js"), "application/javascript");
+    }
+
+    #[test]
+    fn mime_for_extension_unknown_defaults_to_octet_stream() {
+        assert_eq!(mime_for_extension("xyz"), "application/octet-stream");
+    }
+}
@@ -80,6 +80,7 @@ fn help_lists_all_subcommands() {
     assert!(stdout.contains("download"), "help should list download");
     assert!(stdout.contains("list"), "help should list list");
     assert!(stdout.contains("audit"), "help should list audit");
+    assert!(stdout.contains("serve"), "help should list serve");
     assert!(stdout.contains("version"), "help should list version");
 }
 
@@ -99,11 +100,19 @@ fn download_help_lists_new_flags() {
         "--images-dir",
         "--download-files",
         "--create-archive",
+        "--resume",
+        "--sign-key",
     ] {
         assert!(stdout.contains(flag), "download help should list {flag}");
     }
 }
 
+#[test]
+fn audit_help_lists_verify_key_flag() {
+    let stdout = stdout_of(&["audit", "--help"]);
+    assert!(stdout.contains("--verify-key"), "audit help should list --verify-key");
+}
+
 // ===========================================================================
 // Version output
 // ===========================================================================
@@ -145,6 +154,63 @@ async fn download_with_valid_args_exits_zero() {
     assert!(output.status.success(), "download valid args failed: {:?}", output);
 }
 
+#[tokio::test]
+async fn download_follows_redirect_from_custom_domain_and_exits_zero() {
+    let real_server = start_mock().await;
+    let custom_domain = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/posts"))
+        .respond_with(
+            ResponseTemplate::new(301)
+                .insert_header("Location", format!("{}/api/v1/posts", real_server.uri())),
+        )
+        .mount(&custom_domain)
+        .await;
+
+    let output = cli_cmd()
+        .args(["download", "--url", &custom_domain.uri()])
+        .output()
+        .expect("failed");
+    assert!(
+        output.status.success(),
+        "download through a custom-domain redirect failed: {:?}",
+        output
+    );
+}
+
+#[tokio::test]
+async fn download_retries_after_429_with_retry_after_header_and_exits_zero() {
+    let mock_server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/posts"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/posts"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "posts": [],
+            "total": 0,
+            "limit": 50,
+            "offset": 0
+        })))
+        .mount(&mock_server)
+        .await;
+
+    let output = cli_cmd()
+        .args(["download", "--url", &mock_server.uri()])
+        .output()
+        .expect("failed");
+    assert!(
+        output.status.success(),
+        "download did not recover from a throttled 429 response: {:?}",
+        output
+    );
+}
+
 #[tokio::test]
 async fn download_dry_run_exits_zero() {
     let mock_server = start_mock().await;
@@ -187,6 +253,130 @@ async fn download_with_format_md_exits_zero() {
     assert!(output.status.success());
 }
 
+#[tokio::test]
+async fn download_with_format_single_exits_zero() {
+    let mock_server = start_mock().await;
+    let output = cli_cmd()
+        .args(["download", "--url", &mock_server.uri(), "--format", "single"])
+        .output()
+        .expect("failed");
+    assert!(output.status.success());
+}
+
+#[tokio::test]
+async fn download_with_compress_zstd_exits_zero() {
+    let mock_server = start_mock().await;
+    let output = cli_cmd()
+        .args(["download", "--url", &mock_server.uri(), "--compress", "zstd"])
+        .output()
+        .expect("failed");
+    assert!(output.status.success());
+}
+
+#[tokio::test]
+async fn download_with_domain_allow_and_deny_exits_zero() {
+    let mock_server = start_mock().await;
+    let output = cli_cmd()
+        .args([
+            "download",
+            "--url",
+            &mock_server.uri(),
+            "--domain-allow",
+            "substackcdn.com",
+            "--domain-deny",
+            "evil.substackcdn.com",
+        ])
+        .output()
+        .expect("failed");
+    assert!(output.status.success());
+}
+
+#[tokio::test]
+async fn download_with_resume_flag_exits_zero() {
+    let mock_server = start_mock().await;
+    let output = cli_cmd()
+        .args(["download", "--url", &mock_server.uri(), "--resume"])
+        .output()
+        .expect("failed");
+    assert!(output.status.success());
+}
+
+#[tokio::test]
+async fn download_sign_key_then_audit_verify_key_roundtrip() {
+    let mock_server = start_mock().await;
+    let dir = std::env::temp_dir().join("robustack_test_sign_verify_roundtrip");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // 32 raw bytes is a valid ed25519 secret key file; `audit` takes the
+    // matching public key.
+    let key_path = dir.join("signer.key");
+    std::fs::write(&key_path, [42u8; 32]).unwrap();
+    let pub_key_path = dir.join("signer.pub");
+    let public_key = ed25519_dalek::SigningKey::from_bytes(&[42u8; 32]).verifying_key();
+    std::fs::write(&pub_key_path, public_key.to_bytes()).unwrap();
+
+    let download_output = cli_cmd()
+        .args([
+            "download",
+            "--url",
+            &mock_server.uri(),
+            "--output",
+            dir.to_str().unwrap(),
+            "--sign-key",
+            key_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed");
+    assert!(download_output.status.success());
+    assert!(dir.join("manifest.json.sig").exists(), "signed download should write manifest.json.sig");
+
+    let audit_output = cli_cmd()
+        .args([
+            "audit",
+            "--manifest",
+            dir.join("manifest.json").to_str().unwrap(),
+            "--verify-key",
+            pub_key_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed");
+    assert!(audit_output.status.success());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn audit_verify_key_rejects_unsigned_manifest() {
+    let mock_server = start_mock().await;
+    let dir = std::env::temp_dir().join("robustack_test_verify_key_unsigned");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let key_path = dir.join("signer.key");
+    std::fs::write(&key_path, [7u8; 32]).unwrap();
+
+    let download_output = cli_cmd()
+        .args(["download", "--url", &mock_server.uri(), "--output", dir.to_str().unwrap()])
+        .output()
+        .expect("failed");
+    assert!(download_output.status.success());
+
+    let audit_output = cli_cmd()
+        .args([
+            "audit",
+            "--manifest",
+            dir.join("manifest.json").to_str().unwrap(),
+            "--verify-key",
+            key_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("failed");
+    assert!(!audit_output.status.success(), "unsigned manifest should fail --verify-key");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
 #[tokio::test]
 async fn download_with_image_options_exits_zero() {
     let mock_server = start_mock().await;
@@ -244,6 +434,31 @@ async fn list_with_url_exits_zero() {
     assert!(output.status.success(), "list with valid url failed: {:?}", output);
 }
 
+#[tokio::test]
+async fn list_follows_redirect_from_custom_domain_and_exits_zero() {
+    let real_server = start_mock().await;
+    let custom_domain = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(path("/api/v1/posts"))
+        .respond_with(
+            ResponseTemplate::new(301)
+                .insert_header("Location", format!("{}/api/v1/posts", real_server.uri())),
+        )
+        .mount(&custom_domain)
+        .await;
+
+    let output = cli_cmd()
+        .args(["list", "--url", &custom_domain.uri()])
+        .output()
+        .expect("failed");
+    assert!(
+        output.status.success(),
+        "list through a custom-domain redirect failed: {:?}",
+        output
+    );
+}
+
 // ===========================================================================
 // Global flags (Async wrappers where needed for download)
 // ===========================================================================
@@ -341,3 +556,21 @@ async fn short_flags_accepted() {
         .expect("failed");
     assert!(output.status.success());
 }
+
+// ===========================================================================
+// Serve subcommand
+// ===========================================================================
+
+#[test]
+fn serve_help_lists_flags() {
+    let stdout = stdout_of(&["serve", "--help"]);
+    for flag in ["--dir", "--bind", "--auth"] {
+        assert!(stdout.contains(flag), "serve help should list {flag}");
+    }
+}
+
+#[test]
+fn serve_with_invalid_bind_exits_nonzero() {
+    let stderr = stderr_of(&["serve", "--dir", ".", "--bind", "not-an-address"]);
+    assert!(stderr.contains("--bind"), "should report the invalid --bind address");
+}